@@ -1,19 +1,47 @@
 //src/app.rs
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use clap::ValueEnum;
+
+// My Imports
+use crate::backend::RemoteModel;
+use crate::catalog::Catalog;
+use crate::config::PathsConfig;
+use crate::download::{DownloadProgress, DownloadState};
+use crate::gguf::ModelDetails;
+use crate::logs::LogView;
+
+// Where the GGUF files live by default
+pub const MODELS_DIR: &str = "models";
 
 // Posible Screen
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
 pub enum CurrentScreen {
     Welcome,
     Config,
     Chat,
+    Logs,
 }
 
 // App Struct
 pub struct App {
     pub current_screen: CurrentScreen,
-    pub download_progress: u16,
-    pub engine_installed: bool,
+    pub download_progress: Vec<DownloadProgress>,
     pub models: Vec<String>,
+    pub models_dir: PathBuf,
+    // The ones actually sitting in models/
+    pub installed: Vec<String>,
+    pub catalog: Catalog,
+    pub paths: PathsConfig,
+    // "loaded", "loading", "unloaded"... as /models reports them
+    pub model_status: HashMap<String, String>,
+    // GGUF headers, read once per model
+    pub model_details: HashMap<String, ModelDetails>,
     pub selected_model_index: usize,
+    pub log_view: LogView,
 }
 
 // App Methods
@@ -21,18 +49,119 @@ impl App {
     pub fn new() -> Self {
         Self {
             current_screen: CurrentScreen::Welcome,
-            download_progress: 0,
-            engine_installed: false,
-            models: vec!["phi2.gguf".into(), "qwen.gguf".into(), "danube.gguf".into()],
+            download_progress: Vec::new(),
+            // Filled once the config says where the models are
+            models: Vec::new(),
+            models_dir: PathBuf::from(MODELS_DIR),
+            installed: Vec::new(),
+            catalog: Catalog::default(),
+            paths: PathsConfig::default(),
+            model_status: HashMap::new(),
+            model_details: HashMap::new(),
             selected_model_index: 0,
+            log_view: LogView::new(),
         }
     }
 
-    pub fn to_chat(&mut self) {
+    pub fn go_chat(&mut self) {
         self.current_screen = CurrentScreen::Chat;
     }
 
-    pub fn to_config(&mut self) {
+    pub fn go_config(&mut self) {
         self.current_screen = CurrentScreen::Config;
     }
-}
\ No newline at end of file
+
+    pub fn go_logs(&mut self) {
+        self.log_view.scroll = 0;
+        self.current_screen = CurrentScreen::Logs;
+    }
+
+    // Keeps one entry per file, finished downloads show up in the model list
+    pub fn update_download(&mut self, progress: DownloadProgress) {
+        if progress.state == DownloadState::Done {
+            self.refresh_models();
+        }
+        match self.download_progress.iter_mut().find(|p| p.name == progress.name) {
+            Some(entry) => *entry = progress,
+            None => self.download_progress.push(progress),
+        }
+    }
+
+    // Where an installed model lives
+    pub fn model_path(&self, name: &str) -> PathBuf {
+        model_path(&self.models_dir, name)
+    }
+
+    // Moves the highlight to a model, if we know it
+    pub fn select_model(&mut self, name: &str) {
+        if let Some(index) = self.models.iter().position(|m| m == name) {
+            self.selected_model_index = index;
+        }
+    }
+
+    pub fn selected_model(&self) -> Option<&String> {
+        self.models.get(self.selected_model_index)
+    }
+
+    // Reads catalog.toml, its models show up in the list even before downloading
+    pub fn load_catalog(&mut self) -> Result<(), String> {
+        self.catalog = Catalog::load(&self.paths.catalog)?;
+        self.merge_models(self.catalog.names());
+        Ok(())
+    }
+
+    // Points the model list at another folder
+    pub fn set_models_dir(&mut self, dir: PathBuf) {
+        self.models_dir = dir;
+        self.model_details.clear();
+        self.refresh_models();
+    }
+
+    // Rescans the models folder, keeping whatever the server told us about
+    pub fn refresh_models(&mut self) {
+        self.installed = discover_models(&self.models_dir);
+        self.merge_models(self.installed.clone());
+    }
+
+    // What the server says about each model, new ones join the list
+    pub fn update_status(&mut self, models: &[RemoteModel]) {
+        self.model_status.clear();
+        for model in models {
+            if let Some(status) = &model.status {
+                self.model_status.insert(model.id.clone(), status.clone());
+            }
+        }
+        self.merge_models(models.iter().map(|m| m.id.clone()).collect());
+    }
+
+    // Adds models we didn't know about (from the folder or the server's /models)
+    pub fn merge_models(&mut self, names: Vec<String>) {
+        for name in names {
+            if !self.models.contains(&name) {
+                self.models.push(name);
+            }
+        }
+        self.models.sort();
+        if self.selected_model_index >= self.models.len() {
+            self.selected_model_index = self.models.len().saturating_sub(1);
+        }
+    }
+}
+
+// Where an installed model lives
+pub fn model_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.gguf", name))
+}
+
+// Every *.gguf in the folder, named like the router names them (no extension)
+pub fn discover_models(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "gguf"))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,244 @@
+// src/backend.rs
+
+// Generic Imports
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+
+// My Imports
+use crate::chat::{Message, Sampling};
+use crate::event::ClientEvent;
+use crate::llama::LlamaServer;
+use crate::openai::OpenAiServer;
+use crate::settings::API_KEY_ENV;
+use crate::stats::ServerStats;
+
+// Errors that can travel between tasks
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+// Which kind of server answers, set with backend = "..." under [server]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    // llama.cpp's llama-server in router mode, the only one we can launch
+    Llama,
+    // Anything speaking /v1 (Ollama, vLLM, a mock...)
+    OpenAi,
+}
+
+impl BackendKind {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "llama" => Some(BackendKind::Llama),
+            "openai" => Some(BackendKind::OpenAi),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BackendKind::Llama => "llama",
+            BackendKind::OpenAi => "openai",
+        }
+    }
+}
+
+// A model the server knows about, status only if the server tracks it
+#[derive(Clone)]
+pub struct RemoteModel {
+    pub id: String,
+    pub status: Option<String>,
+}
+
+// What Chalante needs from an inference server.
+// Answers are streamed through tx as ClientEvent::Token.
+#[async_trait]
+pub trait Backend: Send + Sync {
+    fn url(&self) -> &str;
+    // Something readable for "get health"
+    async fn health(&self) -> Result<String, BoxError>;
+    // Ok(false) while the server is up but still loading
+    async fn is_ready(&self) -> Result<bool, BoxError>;
+    async fn list_models(&self) -> Result<Vec<RemoteModel>, BoxError>;
+    async fn load_model(&self, model: &str) -> Result<String, BoxError>;
+    async fn unload_model(&self, model: &str) -> Result<String, BoxError>;
+    // A raw prompt, continued as-is
+    async fn complete(&self, model: &str, prompt: &str, sampling: &Sampling, tx: &UnboundedSender<ClientEvent>) -> Result<(), BoxError>;
+    // The conversation, formatted by the server's chat template
+    async fn chat(&self, model: &str, messages: &[Message], sampling: &Sampling, tx: &UnboundedSender<ClientEvent>) -> Result<(), BoxError>;
+    // Context, slots, throughput and KV cache, as far as the server tells
+    async fn stats(&self, model: &str) -> Result<ServerStats, BoxError>;
+}
+
+// The backend for kind, talking to url
+pub fn connect(kind: BackendKind, client: Client, url: String, api_key: Option<String>) -> Arc<dyn Backend> {
+    let connection = Connection { client, url, api_key };
+    match kind {
+        BackendKind::Llama => Arc::new(LlamaServer { connection }),
+        BackendKind::OpenAi => Arc::new(OpenAiServer { connection }),
+    }
+}
+
+// Where and how to send requests, shared by every backend
+#[derive(Clone)]
+pub struct Connection {
+    pub client: Client,
+    pub url: String,
+    // Sent as "Authorization: Bearer" on every request
+    pub api_key: Option<String>,
+}
+
+impl Connection {
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.with_key(self.client.get(format!("{}/{}", &self.url, path)))
+    }
+
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.with_key(self.client.post(format!("{}/{}", &self.url, path)))
+    }
+
+    fn with_key(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+}
+
+// The server wants an API key we don't have (or a different one)
+#[derive(Debug)]
+pub struct Unauthorized;
+
+impl std::fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "401 Unauthorized, set api_key in the config or {}", API_KEY_ENV)
+    }
+}
+
+impl std::error::Error for Unauthorized {}
+
+// A 401 gets its own error, everything else goes through untouched
+pub fn authorized(res: Response) -> Result<Response, BoxError> {
+    if res.status() == StatusCode::UNAUTHORIZED {
+        return Err(Unauthorized.into());
+    }
+    Ok(res)
+}
+
+// Reads a Server-Sent Events stream, one ClientEvent::Token per piece of text
+pub async fn stream_tokens(
+    request: RequestBuilder,
+    body: serde_json::Value,
+    tx: &UnboundedSender<ClientEvent>,
+) -> Result<(), BoxError> {
+    let mut res = authorized(request.json(&body).send().await?)?
+        .error_for_status()?;
+
+    let mut decoder = SseDecoder::default();
+    let mut tokens = Vec::new();
+    while let Some(chunk) = res.chunk().await? {
+        let done = decoder.push(&chunk, &mut tokens)?;
+        for token in tokens.drain(..) {
+            let _ = tx.send(ClientEvent::Token(token));
+        }
+        if done {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+// Chunks don't respect line (or UTF-8) boundaries, so we buffer bytes
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    // Adds the tokens of every line chunk completes, true once the stream says it's over
+    fn push(&mut self, chunk: &[u8], tokens: &mut Vec<String>) -> Result<bool, BoxError> {
+        self.buffer.extend_from_slice(chunk);
+
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let Some(data) = line.trim().strip_prefix("data:") else { continue };
+            let data = data.trim();
+            // OpenAI-style end of stream
+            if data == "[DONE]" {
+                return Ok(true);
+            }
+
+            // llama.cpp's /completion, /v1/chat/completions and /v1/completions
+            let event: serde_json::Value = serde_json::from_str(data)?;
+            let token = event["content"]
+                .as_str()
+                .or_else(|| event["choices"][0]["delta"]["content"].as_str())
+                .or_else(|| event["choices"][0]["text"].as_str());
+            if let Some(token) = token
+                && !token.is_empty() {
+                tokens.push(token.to_string());
+            }
+            if event["stop"].as_bool().unwrap_or(false) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Feeds the stream in pieces of size bytes, like a slow connection would
+    fn decode(stream: &str, size: usize) -> (Vec<String>, bool) {
+        let mut decoder = SseDecoder::default();
+        let mut tokens = Vec::new();
+        for chunk in stream.as_bytes().chunks(size) {
+            if decoder.push(chunk, &mut tokens).unwrap() {
+                return (tokens, true);
+            }
+        }
+        (tokens, false)
+    }
+
+    #[test]
+    fn lines_survive_any_chunk_boundary() {
+        // Two-byte "é" and four-byte "🦙" get cut in half by the small sizes
+        let stream = "data: {\"content\":\"Hola\"}\n\ndata: {\"content\":\" qué 🦙\"}\n\ndata: {\"content\":\"\",\"stop\":true}\n\n";
+        for size in 1..=stream.len() {
+            let (tokens, done) = decode(stream, size);
+            assert_eq!(tokens, ["Hola", " qué 🦙"], "chunks of {}", size);
+            assert!(done, "chunks of {}", size);
+        }
+    }
+
+    #[test]
+    fn openai_streams_end_with_done() {
+        let stream = "data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}\n\n\
+                      data: {\"choices\":[{\"text\":\"!\"}]}\n\n\
+                      data: [DONE]\n\n\
+                      data: {\"content\":\"after the end\"}\n\n";
+        assert_eq!(decode(stream, 7), (vec!["Hi".to_string(), "!".to_string()], true));
+    }
+
+    #[test]
+    fn other_lines_are_ignored() {
+        let stream = ": keep-alive\nevent: message\ndata: {\"content\":\"ok\"}\n";
+        assert_eq!(decode(stream, 5), (vec!["ok".to_string()], false));
+    }
+
+    #[test]
+    fn an_unfinished_line_waits() {
+        assert_eq!(decode("data: {\"content\":\"half", 64), (Vec::new(), false));
+    }
+
+    #[test]
+    fn broken_json_is_an_error() {
+        let mut tokens = Vec::new();
+        assert!(SseDecoder::default().push(b"data: not json\n", &mut tokens).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+// src/catalog.rs
+
+// Generic Imports
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+// Where the catalog lives (next to models/ and scripts/)
+pub const CATALOG_FILE: &str = "catalog.toml";
+
+// How the model wants to be prompted
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateHint {
+    // The GGUF ships a chat template, let llama-server apply it
+    Embedded,
+    // No usable template, fall back to the raw "User: / Assistant:" prompt
+    Raw,
+}
+
+// One entry of catalog.toml
+#[derive(Clone, Deserialize)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub repo: String,
+    pub filename: String,
+    pub quantization: String,
+    pub size_mb: Option<u64>,
+    pub sha256: Option<String>,
+    pub context: Option<u32>,
+    pub template: Option<TemplateHint>,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+}
+
+impl CatalogEntry {
+    pub fn url(&self) -> String {
+        format!("https://huggingface.co/{}/resolve/main/{}?download=true", self.repo, self.filename)
+    }
+
+    // "- qwen: Q8_0, ~676 MB, 32768 ctx"
+    pub fn summary(&self) -> String {
+        let mut line = format!("- {}: {}", self.name, self.quantization);
+        if let Some(size) = self.size_mb {
+            line.push_str(&format!(", ~{} MB", size));
+        }
+        if let Some(context) = self.context {
+            line.push_str(&format!(", {} ctx", context));
+        }
+        line
+    }
+}
+
+// The whole file
+#[derive(Clone, Default, Deserialize)]
+pub struct Catalog {
+    #[serde(rename = "model", default)]
+    pub models: Vec<CatalogEntry>,
+}
+
+impl Catalog {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let raw = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        toml::from_str(&raw).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CatalogEntry> {
+        self.models.iter().find(|m| m.name == name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.models.iter().map(|m| m.name.clone()).collect()
+    }
+}
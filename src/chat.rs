@@ -0,0 +1,60 @@
+// src/chat.rs
+use serde::{Deserialize, Serialize};
+
+// Who said what
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+impl Role {
+    // How the role is written in the raw prompt
+    pub fn label(&self) -> &'static str {
+        match self {
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+        }
+    }
+}
+
+// One turn of the conversation, kept next to the styled history
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+}
+
+impl Message {
+    pub fn new(role: Role, content: &str) -> Self {
+        Self { role, content: content.to_string() }
+    }
+}
+
+// How the model is asked to answer
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sampling {
+    pub n_predict: u32,
+    pub temperature: f32,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self { n_predict: 200, temperature: 0.2 }
+    }
+}
+
+// Where a raw completion should stop, so the model doesn't answer for the user
+pub const STOP: [&str; 5] = ["User:", "Assistant:", "\nUser:", "<|im_end|>", "<|endoftext|>"];
+
+// Flattens the whole conversation into a "User: / Assistant:" prompt
+pub fn build_prompt(messages: &[Message]) -> String {
+    let mut prompt = String::new();
+    for message in messages {
+        prompt.push_str(&format!("\n{}: {}", message.role.label(), message.content));
+    }
+    prompt.push_str("\nAssistant:");
+    prompt
+}
@@ -0,0 +1,61 @@
+// src/cli.rs
+
+// Generic Imports
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+// My Imports
+use crate::app::CurrentScreen;
+
+// chalante [--model qwen] [--url http://host:port] [--config file] [--start-server] [--chat]
+// chalante ask "write a commit message" < diff
+#[derive(Parser)]
+#[command(name = "chalante", version, about = "A terminal front-end for llama.cpp")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Model to chat with (overrides the config and the last chat)
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Connect to a llama-server that is already running, e.g. http://127.0.0.1:11343
+    #[arg(long)]
+    pub url: Option<String>,
+
+    /// Config file to use instead of the one in the config directory
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Start llama-server right away and load the model once it's ready
+    // A --url server is someone else's, never ours to start
+    #[arg(long, conflicts_with = "url")]
+    pub start_server: bool,
+
+    /// Screen to open on
+    #[arg(long, value_enum)]
+    pub screen: Option<CurrentScreen>,
+
+    /// Open on the Chat screen (same as --screen chat)
+    #[arg(long, conflicts_with = "screen")]
+    pub chat: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Ask once without the TUI, the answer is streamed to stdout
+    Ask {
+        /// The question, anything piped in is appended after it
+        prompt: Vec<String>,
+    },
+}
+
+impl Cli {
+    pub fn screen(&self) -> Option<CurrentScreen> {
+        if self.chat {
+            Some(CurrentScreen::Chat)
+        } else {
+            self.screen
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// src/config.rs
+
+// Generic Imports
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// My Imports
+use crate::app::CurrentScreen;
+use crate::catalog::CATALOG_FILE;
+use crate::chat::Sampling;
+use crate::llama::Endpoint;
+use crate::settings::ServerSettings;
+
+// Lives in the user's config directory (~/.config/chalante/config.toml on Linux)
+pub const CONFIG_FILE: &str = "config.toml";
+
+// Everything Chalante starts with, missing keys keep their default
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub server: ServerSettings,
+    pub sampling: Sampling,
+    pub ui: UiConfig,
+    pub paths: PathsConfig,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    // Model used by "load model" and new chats
+    pub model: String,
+    pub endpoint: Endpoint,
+    // Screen shown at launch
+    pub screen: CurrentScreen,
+    // Reopen the conversation we left on exit
+    pub restore_last: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            model: "qwen".to_string(),
+            endpoint: Endpoint::Chat,
+            screen: CurrentScreen::Welcome,
+            restore_last: true,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathsConfig {
+    pub catalog: PathBuf,
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        Self { catalog: PathBuf::from(CATALOG_FILE) }
+    }
+}
+
+// None when the platform has no config directory
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("chalante").join(CONFIG_FILE))
+}
+
+impl Config {
+    // A missing file just means "all defaults"
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
@@ -1,11 +1,22 @@
 // src/download.rs
 use tokio::process::Command;
+use tokio::fs;
 use std::process::Stdio;
-use tokio::io::{BufReader, AsyncBufReadExt};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use reqwest::{header::{CONTENT_RANGE, RANGE}, redirect::Policy, Client, Response, StatusCode};
+use sha2::{Digest, Sha256};
+use tokio::io::{BufReader, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
 use tokio::sync::mpsc::UnboundedSender;
 
+// My Imports
+use crate::app::model_path;
+use crate::backend::BoxError;
+use crate::catalog::CatalogEntry;
+use crate::event::ClientEvent;
+
 /// Installs the llama.cpp engine and captures both stdout and stderr to prevent terminal leakage.
-pub async fn install_engine(tx: UnboundedSender<String>) {
+pub async fn install_engine(tx: UnboundedSender<ClientEvent>) {
     let mut child = Command::new("sh")
         .arg("./scripts/engine.sh")
         .stdout(Stdio::piped())
@@ -24,7 +35,7 @@ pub async fn install_engine(tx: UnboundedSender<String>) {
         tokio::select! {
             res = stdout_reader.next_line() => {
                 match res {
-                    Ok(Some(line)) => { let _ = tx.send(line); }
+                    Ok(Some(line)) => { let _ = tx.send(ClientEvent::Log(line)); }
                     Ok(None) => break, // stdout closed, engine.sh is finishing
                     Err(_) => break,
                 }
@@ -32,48 +43,341 @@ pub async fn install_engine(tx: UnboundedSender<String>) {
             res = stderr_reader.next_line() => {
                 if let Ok(Some(line)) = res {
                     // Prepend [LOG] or filter if you want to distinguish errors/progress
-                    let _ = tx.send(format!("[LOG] {}", line));
+                    let _ = tx.send(ClientEvent::Log(format!("[LOG] {}", line)));
                 }
             }
         }
     }
     
     let _ = child.wait().await;
-    let _ = tx.send("Engine Installation Complete!".to_string());
+    let _ = tx.send(ClientEvent::Log("Engine Installation Complete!".to_string()));
 }
 
-/// Installs the models and ensures no stderr output leaks into the TUI.
-pub async fn install_models(tx: UnboundedSender<String>) {
-    let mut child = Command::new("sh")
-        .arg("./scripts/models.sh")
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped()) // Ensure curl/wget logs don't overwrite the UI
-        .spawn()
-        .expect("Failed to execute models.sh");
+// How often a download reports back to the TUI
+const REPORT_EVERY: Duration = Duration::from_millis(200);
 
-    let stdout = child.stdout.take().unwrap();
-    let stderr = child.stderr.take().unwrap();
+#[derive(Clone, PartialEq)]
+pub enum DownloadState {
+    Downloading,
+    Verifying,
+    Done,
+    Skipped,
+    Failed(String),
+}
 
-    let mut stdout_reader = BufReader::new(stdout).lines();
-    let mut stderr_reader = BufReader::new(stderr).lines();
+// Progress of one file, shown as a gauge on the Config screen
+#[derive(Clone)]
+pub struct DownloadProgress {
+    pub name: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    // Bytes per second
+    pub speed: f64,
+    pub state: DownloadState,
+}
 
-    loop {
-        tokio::select! {
-            res = stdout_reader.next_line() => {
-                match res {
-                    Ok(Some(line)) => { let _ = tx.send(format!("Downloading: {}", line)); }
-                    Ok(None) => break,
-                    Err(_) => break,
-                }
+impl DownloadProgress {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            downloaded: 0,
+            total: None,
+            speed: 0.0,
+            state: DownloadState::Downloading,
+        }
+    }
+
+    pub fn percent(&self) -> u16 {
+        match (&self.state, self.total) {
+            (DownloadState::Done | DownloadState::Skipped, _) => 100,
+            (_, Some(total)) if total > 0 => (self.downloaded * 100 / total).min(100) as u16,
+            _ => 0,
+        }
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.total?.saturating_sub(self.downloaded);
+        (self.speed > 0.0).then(|| Duration::from_secs_f64(remaining as f64 / self.speed))
+    }
+
+    // "120.5 / 500.0 MB  3.2 MB/s  ETA 2m05s"
+    pub fn label(&self) -> String {
+        let mb = |bytes: u64| bytes as f64 / 1_000_000.0;
+        match &self.state {
+            DownloadState::Verifying => format!("{} verifying checksum...", self.name),
+            DownloadState::Done => format!("{} done ({:.1} MB)", self.name, mb(self.downloaded)),
+            DownloadState::Skipped => format!("{} already installed", self.name),
+            DownloadState::Failed(e) => format!("{} failed: {}", self.name, e),
+            DownloadState::Downloading => {
+                let size = match self.total {
+                    Some(total) => format!("{:.1} / {:.1} MB", mb(self.downloaded), mb(total)),
+                    None => format!("{:.1} MB", mb(self.downloaded)),
+                };
+                let eta = match self.eta() {
+                    Some(eta) => format!("ETA {}m{:02}s", eta.as_secs() / 60, eta.as_secs() % 60),
+                    None => "ETA --".to_string(),
+                };
+                format!("{}  {}  {:.1} MB/s  {}", self.name, size, self.speed / 1_000_000.0, eta)
             }
-            res = stderr_reader.next_line() => {
-                if let Ok(Some(line)) = res {
-                    let _ = tx.send(format!("[DL-LOG] {}", line));
-                }
+        }
+    }
+}
+
+/// Downloads the catalog models one by one, reporting progress as events.
+/// Partial files are kept as <name>.gguf.part and resumed on the next run.
+/// Without verify, entries with no known sha256 are installed unchecked.
+pub async fn install_models(client: Client, models: Vec<CatalogEntry>, dir: PathBuf, verify: bool, tx: UnboundedSender<ClientEvent>) {
+    if let Err(e) = fs::create_dir_all(&dir).await {
+        let _ = tx.send(ClientEvent::Log(format!("Error: Could not create {}: {}", dir.display(), e)));
+        return;
+    }
+
+    for entry in &models {
+        let path = model_path(&dir, &entry.name);
+        let part = dir.join(format!("{}.gguf.part", entry.name));
+        let mut progress = DownloadProgress::new(&entry.name);
+        // Until the server tells us the real size
+        progress.total = entry.size_mb.map(|mb| mb * 1_000_000);
+
+        if fs::try_exists(&path).await.unwrap_or(false) {
+            progress.state = DownloadState::Skipped;
+            let _ = tx.send(ClientEvent::Download(progress));
+            continue;
+        }
+
+        progress.state = match install(&client, entry, &part, &path, verify, &mut progress, &tx).await {
+            Ok(()) => DownloadState::Done,
+            Err(e) => DownloadState::Failed(e.to_string()),
+        };
+        let _ = tx.send(ClientEvent::Download(progress));
+    }
+
+    let _ = tx.send(ClientEvent::Log("Models Installed!".to_string()));
+}
+
+// Download to .part, verify, then move into models/
+async fn install(
+    client: &Client,
+    entry: &CatalogEntry,
+    part: &Path,
+    path: &Path,
+    verify: bool,
+    progress: &mut DownloadProgress,
+    tx: &UnboundedSender<ClientEvent>,
+) -> Result<(), BoxError> {
+    // Pinned in the catalog, or the one Hugging Face publishes
+    let checksum = match &entry.sha256 {
+        Some(sha256) => Some(sha256.clone()),
+        None => published_sha256(&entry.url()).await,
+    };
+    // No point downloading what we would refuse to install
+    if verify && checksum.is_none() {
+        return Err("no sha256 in the catalog or from Hugging Face, add one or run \"install models unverified\"".into());
+    }
+    download(client, &entry.url(), part, progress, tx).await?;
+
+    match &checksum {
+        Some(expected) => {
+            progress.state = DownloadState::Verifying;
+            let _ = tx.send(ClientEvent::Download(progress.clone()));
+            let actual = sha256(part).await?;
+            if !actual.eq_ignore_ascii_case(expected) {
+                // A corrupt file can't be resumed, start over next time
+                let _ = fs::remove_file(part).await;
+                return Err(format!("checksum mismatch (got {})", actual).into());
             }
         }
+        None => {
+            let _ = tx.send(ClientEvent::Log(format!("[DL-LOG] No checksum for {}, not verified", entry.name)));
+        }
     }
-    
-    let _ = child.wait().await;
-    let _ = tx.send("Models Installed!".to_string());
-}
\ No newline at end of file
+
+    fs::rename(part, path).await?;
+    Ok(())
+}
+
+// Streams one file to disk, resuming with a Range request if part of it is there
+async fn download(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    progress: &mut DownloadProgress,
+    tx: &UnboundedSender<ClientEvent>,
+) -> Result<(), BoxError> {
+    let mut offset = fs::metadata(part).await.map(|m| m.len()).unwrap_or(0);
+
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={}-", offset));
+    }
+    let mut res = request.send().await?;
+
+    // The Range starts at or past the end of the file
+    if offset > 0 && res.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        // The .part file already holds everything (the checksum still gets the last word)
+        if content_range(&res).and_then(|(_, length)| length) == Some(offset) {
+            progress.downloaded = offset;
+            progress.total = Some(offset);
+            return Ok(());
+        }
+        // Bigger than the file, or the server won't say: start over
+        fs::remove_file(part).await?;
+        offset = 0;
+        res = client.get(url).send().await?;
+    }
+    let mut res = res.error_for_status()?;
+
+    // Servers that ignore Range send the whole file again
+    if res.status() != StatusCode::PARTIAL_CONTENT {
+        offset = 0;
+    } else if content_range(&res).and_then(|(first, _)| first) != Some(offset) {
+        // Not the bytes we asked for, appending them would corrupt the file
+        fs::remove_file(part).await?;
+        offset = 0;
+        res = client.get(url).send().await?.error_for_status()?;
+    }
+    progress.downloaded = offset;
+    progress.total = res.content_length().map(|len| len + offset);
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(part)
+        .await?;
+    let started = Instant::now();
+    let mut last_report = Instant::now();
+
+    while let Some(chunk) = res.chunk().await? {
+        file.write_all(&chunk).await?;
+        progress.downloaded += chunk.len() as u64;
+
+        if last_report.elapsed() >= REPORT_EVERY {
+            progress.speed = (progress.downloaded - offset) as f64 / started.elapsed().as_secs_f64();
+            let _ = tx.send(ClientEvent::Download(progress.clone()));
+            last_report = Instant::now();
+        }
+    }
+    file.flush().await?;
+
+    // Cut short without an error still means an incomplete file
+    if let Some(total) = progress.total
+        && progress.downloaded < total {
+        return Err("connection closed early, run it again to resume".into());
+    }
+    Ok(())
+}
+
+// Hugging Face answers a download with a redirect whose X-Linked-Etag
+// is the file's LFS oid, which is its SHA-256
+async fn published_sha256(url: &str) -> Option<String> {
+    // The header rides on the redirect, so don't follow it
+    let client = Client::builder().redirect(Policy::none()).build().ok()?;
+    let res = client.head(url).send().await.ok()?;
+    parse_linked_etag(res.headers().get("x-linked-etag")?.to_str().ok()?)
+}
+
+// "\"<64 hex digits>\"", maybe weak; anything else is a git blob id or garbage
+fn parse_linked_etag(etag: &str) -> Option<String> {
+    let sha256 = etag.trim().trim_start_matches("W/").trim_matches('"').to_lowercase();
+    (sha256.len() == 64 && sha256.chars().all(|c| c.is_ascii_hexdigit())).then_some(sha256)
+}
+
+// First byte and full length from Content-Range
+fn content_range(res: &Response) -> Option<(Option<u64>, Option<u64>)> {
+    parse_content_range(res.headers().get(CONTENT_RANGE)?.to_str().ok()?)
+}
+
+// "bytes <first>-<last>/<length>" on a 206, "bytes */<length>" on a 416, either may be "*"
+fn parse_content_range(text: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let (range, length) = text.trim().strip_prefix("bytes ")?.split_once('/')?;
+    let first = range.split_once('-').and_then(|(first, _)| first.parse().ok());
+    Some((first, length.parse().ok()))
+}
+
+// Hex SHA-256 of a file, read in chunks so big models don't fill the RAM
+async fn sha256(path: &Path) -> Result<String, BoxError> {
+    let mut file = fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_range_gives_first_byte_and_length() {
+        assert_eq!(parse_content_range("bytes 100-999/1000"), Some((Some(100), Some(1000))));
+        assert_eq!(parse_content_range("bytes 0-499/*"), Some((Some(0), None)));
+        assert_eq!(parse_content_range("bytes */1000"), Some((None, Some(1000))));
+        assert_eq!(parse_content_range("items 0-9/10"), None);
+        assert_eq!(parse_content_range("bytes 0-9"), None);
+    }
+
+    #[test]
+    fn linked_etag_is_a_sha256() {
+        let sha256 = "a".repeat(64);
+        assert_eq!(parse_linked_etag(&format!("\"{}\"", sha256)), Some(sha256.clone()));
+        assert_eq!(parse_linked_etag(&format!("W/\"{}\"", sha256.to_uppercase())), Some(sha256));
+        // Files outside LFS get their git blob id (40 digits)
+        assert_eq!(parse_linked_etag(&format!("\"{}\"", "b".repeat(40))), None);
+        assert_eq!(parse_linked_etag(&format!("\"{}\"", "z".repeat(64))), None);
+    }
+
+    #[test]
+    fn percent_follows_the_state() {
+        let mut progress = DownloadProgress::new("qwen");
+        assert_eq!(progress.percent(), 0);
+
+        progress.total = Some(400);
+        progress.downloaded = 100;
+        assert_eq!(progress.percent(), 25);
+
+        // A server that sends more than it announced
+        progress.downloaded = 500;
+        assert_eq!(progress.percent(), 100);
+
+        progress.downloaded = 0;
+        progress.state = DownloadState::Skipped;
+        assert_eq!(progress.percent(), 100);
+
+        progress.state = DownloadState::Failed("gone".to_string());
+        assert_eq!(progress.percent(), 0);
+    }
+
+    #[test]
+    fn eta_needs_a_size_and_a_speed() {
+        let mut progress = DownloadProgress::new("qwen");
+        progress.speed = 100.0;
+        assert_eq!(progress.eta(), None);
+
+        progress.total = Some(1_000);
+        progress.downloaded = 400;
+        assert_eq!(progress.eta(), Some(Duration::from_secs(6)));
+
+        progress.speed = 0.0;
+        assert_eq!(progress.eta(), None);
+    }
+
+    #[test]
+    fn label_shows_size_speed_and_eta() {
+        let mut progress = DownloadProgress::new("qwen");
+        progress.total = Some(500_000_000);
+        progress.downloaded = 120_500_000;
+        progress.speed = 3_200_000.0;
+        assert_eq!(progress.label(), "qwen  120.5 / 500.0 MB  3.2 MB/s  ETA 1m58s");
+
+        progress.state = DownloadState::Verifying;
+        assert_eq!(progress.label(), "qwen verifying checksum...");
+    }
+}
@@ -0,0 +1,46 @@
+// src/event.rs
+use crate::backend::RemoteModel;
+use crate::download::DownloadProgress;
+use crate::gguf::GgufInfo;
+use crate::memory::MemoryReport;
+use crate::stats::ServerStats;
+
+// Everything a background task can tell the TUI.
+// Tasks get a clone of the sender, the main loop drains the receiver every frame.
+pub enum ClientEvent {
+    // A line for the Output/Stats panel (install scripts, progress...)
+    Log(String),
+    // The answer to a command, replaces whatever the panel showed
+    Output(String),
+    // How a model download is going
+    Download(DownloadProgress),
+    // "install models" is over, another one may start
+    InstallFinished,
+    // What the server has to offer
+    Models(Result<Vec<RemoteModel>, String>),
+    // A model finished (or failed) loading
+    ModelLoaded { model: String, result: Result<String, String> },
+    // A model was (or couldn't be) dropped from memory
+    ModelUnloaded { model: String, result: Result<String, String> },
+    // Quiet /models refresh, only for the statuses in the model list
+    ModelStates(Vec<RemoteModel>),
+    // How much RAM our llama-server (and each model in it) holds
+    Memory(MemoryReport),
+    // A poll of /props, /slots and /metrics
+    Stats(Result<ServerStats, String>),
+    // Header of a GGUF file, for the model details panel
+    ModelInfo { model: String, result: Result<GgufInfo, String> },
+    // An output line from our llama-server
+    ServerLog(String),
+    // Startup finished, one way or the other, for the server with that pid
+    ServerReady { pid: u32 },
+    ServerFailed { pid: u32, reason: String },
+    // Whether something answers at url (and whether to start ours if not)
+    ServerProbed { url: String, answering: bool, start: bool },
+    // Our llama-server is gone (and should come back if restart)
+    ServerStopped { restart: bool },
+    // Streaming answers
+    Token(String),
+    Done,
+    Failed(String),
+}
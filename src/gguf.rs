@@ -0,0 +1,370 @@
+// src/gguf.rs
+// Reads the header of a GGUF file (metadata + tensor list), never the weights.
+// Format: https://github.com/ggml-org/ggml/blob/master/docs/gguf.md
+
+// Generic Imports
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::path::Path;
+
+// What the model details panel shows
+#[derive(Clone)]
+pub struct GgufInfo {
+    pub version: u32,
+    pub name: Option<String>,
+    pub architecture: Option<String>,
+    pub parameters: u64,
+    pub quantization: Option<String>,
+    pub context_length: Option<u64>,
+    pub chat_template: Option<String>,
+}
+
+// What App keeps per model while the header is read in the background
+pub enum ModelDetails {
+    Loading,
+    Ready(GgufInfo),
+    Failed(String),
+}
+
+// Metadata values we care about, the rest are skipped
+enum Value {
+    Int(u64),
+    Text(String),
+    Other,
+}
+
+struct Reader<R> {
+    inner: BufReader<R>,
+}
+
+impl<R: Read + Seek> Reader<R> {
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut buf = [0u8; N];
+        self.inner.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes()?))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.bytes()?))
+    }
+
+    fn skip(&mut self, len: u64) -> io::Result<()> {
+        let len = i64::try_from(len).map_err(|_| invalid("length too big"))?;
+        self.inner.seek_relative(len)
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        let len = self.u64()?;
+        // Chat templates are a few KB, anything huge means a broken file
+        if len > 16 * 1024 * 1024 {
+            return Err(invalid("string too long"));
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.inner.read_exact(&mut buf)?;
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    fn skip_string(&mut self) -> io::Result<()> {
+        let len = self.u64()?;
+        self.skip(len)
+    }
+
+    // Reads a value of the given GGUF type, keeping only ints and strings
+    fn value(&mut self, kind: u32) -> io::Result<Value> {
+        Ok(match kind {
+            0 | 1 | 7 => Value::Int(self.bytes::<1>()?[0] as u64),
+            2 | 3 => Value::Int(u16::from_le_bytes(self.bytes()?) as u64),
+            4 | 5 => Value::Int(self.u32()? as u64),
+            10 | 11 => Value::Int(self.u64()?),
+            6 => { self.skip(4)?; Value::Other }
+            12 => { self.skip(8)?; Value::Other }
+            8 => Value::Text(self.string()?),
+            9 => {
+                let item_kind = self.u32()?;
+                let count = self.u64()?;
+                self.skip_array(item_kind, count)?;
+                Value::Other
+            }
+            _ => return Err(invalid("unknown metadata type")),
+        })
+    }
+
+    // Arrays (like the tokenizer vocabulary) can be huge, skip them without allocating
+    fn skip_array(&mut self, kind: u32, count: u64) -> io::Result<()> {
+        let size = match kind {
+            0 | 1 | 7 => 1,
+            2 | 3 => 2,
+            4..=6 => 4,
+            10..=12 => 8,
+            8 => {
+                for _ in 0..count {
+                    self.skip_string()?;
+                }
+                return Ok(());
+            }
+            9 => {
+                for _ in 0..count {
+                    let item_kind = self.u32()?;
+                    let item_count = self.u64()?;
+                    self.skip_array(item_kind, item_count)?;
+                }
+                return Ok(());
+            }
+            _ => return Err(invalid("unknown array type")),
+        };
+        self.skip(count.checked_mul(size).ok_or_else(|| invalid("array too big"))?)
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+// llama.cpp's general.file_type numbers
+fn file_type_name(file_type: u64) -> Option<&'static str> {
+    Some(match file_type {
+        0 => "F32",
+        1 => "F16",
+        2 => "Q4_0",
+        3 => "Q4_1",
+        7 => "Q8_0",
+        8 => "Q5_0",
+        9 => "Q5_1",
+        10 => "Q2_K",
+        11 => "Q3_K_S",
+        12 => "Q3_K_M",
+        13 => "Q3_K_L",
+        14 => "Q4_K_S",
+        15 => "Q4_K_M",
+        16 => "Q5_K_S",
+        17 => "Q5_K_M",
+        18 => "Q6_K",
+        19 => "IQ2_XXS",
+        20 => "IQ2_XS",
+        21 => "Q2_K_S",
+        22 => "IQ3_XS",
+        23 => "IQ3_XXS",
+        24 => "IQ1_S",
+        25 => "IQ4_NL",
+        26 => "IQ3_S",
+        27 => "IQ3_M",
+        28 => "IQ2_S",
+        29 => "IQ2_M",
+        30 => "IQ4_XS",
+        31 => "IQ1_M",
+        32 => "BF16",
+        _ => return None,
+    })
+}
+
+pub fn read_info(path: &Path) -> io::Result<GgufInfo> {
+    parse(File::open(path)?)
+}
+
+// The header from anything seekable, a file or bytes in memory
+fn parse<R: Read + Seek>(source: R) -> io::Result<GgufInfo> {
+    let mut reader = Reader { inner: BufReader::new(source) };
+
+    if &reader.bytes::<4>()? != b"GGUF" {
+        return Err(invalid("not a GGUF file"));
+    }
+    let version = reader.u32()?;
+    // Version 1 used 32-bit counts, nobody ships those anymore
+    if version < 2 {
+        return Err(invalid("GGUF v1 is not supported"));
+    }
+    let tensor_count = reader.u64()?;
+    let kv_count = reader.u64()?;
+
+    let mut info = GgufInfo {
+        version,
+        name: None,
+        architecture: None,
+        parameters: 0,
+        quantization: None,
+        context_length: None,
+        chat_template: None,
+    };
+
+    // Metadata
+    for _ in 0..kv_count {
+        let key = reader.string()?;
+        let kind = reader.u32()?;
+        match (key.as_str(), reader.value(kind)?) {
+            ("general.architecture", Value::Text(arch)) => info.architecture = Some(arch),
+            ("general.name", Value::Text(name)) => info.name = Some(name),
+            ("general.file_type", Value::Int(file_type)) => {
+                info.quantization = file_type_name(file_type).map(String::from);
+            }
+            ("tokenizer.chat_template", Value::Text(template)) => info.chat_template = Some(template),
+            // "<arch>.context_length", e.g. "qwen2.context_length"
+            (key, Value::Int(length)) if key.ends_with(".context_length") => info.context_length = Some(length),
+            _ => {}
+        }
+    }
+
+    // Tensor infos: the parameter count is the sum of every tensor's elements
+    for _ in 0..tensor_count {
+        reader.skip_string()?;
+        let dims = reader.u32()?;
+        let mut elements: u64 = 1;
+        for _ in 0..dims {
+            elements = elements.saturating_mul(reader.u64()?);
+        }
+        // ggml type + data offset
+        reader.skip(4 + 8)?;
+        info.parameters = info.parameters.saturating_add(elements);
+    }
+
+    Ok(info)
+}
+
+// "494M", "2.8B"
+pub fn format_parameters(count: u64) -> String {
+    if count >= 1_000_000_000 {
+        format!("{:.1}B", count as f64 / 1e9)
+    } else {
+        format!("{}M", count / 1_000_000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    // Little-endian GGUF pieces, written the way llama.cpp lays them out
+    fn string(buf: &mut Vec<u8>, text: &str) {
+        buf.extend_from_slice(&(text.len() as u64).to_le_bytes());
+        buf.extend_from_slice(text.as_bytes());
+    }
+
+    fn kv(buf: &mut Vec<u8>, key: &str, kind: u32, value: &[u8]) {
+        string(buf, key);
+        buf.extend_from_slice(&kind.to_le_bytes());
+        buf.extend_from_slice(value);
+    }
+
+    fn reader(bytes: Vec<u8>) -> Reader<Cursor<Vec<u8>>> {
+        Reader { inner: BufReader::new(Cursor::new(bytes)) }
+    }
+
+    // Reading a value of kind must leave the reader right on the marker after it
+    fn assert_consumes(kind: u32, value: &[u8]) {
+        let mut bytes = value.to_vec();
+        bytes.extend_from_slice(&0xC0FFEEu32.to_le_bytes());
+        let mut reader = reader(bytes);
+        reader.value(kind).unwrap();
+        assert_eq!(reader.u32().unwrap(), 0xC0FFEE, "type {}", kind);
+    }
+
+    #[test]
+    fn scalar_types_have_their_sizes() {
+        for (kind, size) in [(0, 1), (1, 1), (7, 1), (2, 2), (3, 2), (4, 4), (5, 4), (6, 4), (10, 8), (11, 8), (12, 8)] {
+            assert_consumes(kind, &vec![0u8; size]);
+        }
+    }
+
+    #[test]
+    fn ints_and_strings_are_kept() {
+        let mut ints = reader([42u16.to_le_bytes().as_slice(), &7u64.to_le_bytes()].concat());
+        assert!(matches!(ints.value(3).unwrap(), Value::Int(42)));
+        assert!(matches!(ints.value(10).unwrap(), Value::Int(7)));
+
+        let mut bytes = Vec::new();
+        string(&mut bytes, "qwen2");
+        assert!(matches!(reader(bytes).value(8).unwrap(), Value::Text(text) if text == "qwen2"));
+    }
+
+    #[test]
+    fn nested_arrays_are_skipped() {
+        // [[u16; 3], [string; 2]]
+        let mut value = Vec::new();
+        value.extend_from_slice(&9u32.to_le_bytes());
+        value.extend_from_slice(&2u64.to_le_bytes());
+        value.extend_from_slice(&3u32.to_le_bytes());
+        value.extend_from_slice(&3u64.to_le_bytes());
+        value.extend_from_slice(&[0u8; 6]);
+        value.extend_from_slice(&8u32.to_le_bytes());
+        value.extend_from_slice(&2u64.to_le_bytes());
+        string(&mut value, "<s>");
+        string(&mut value, "</s>");
+
+        assert_consumes(9, &value);
+    }
+
+    #[test]
+    fn unknown_types_are_rejected() {
+        assert!(reader(vec![0u8; 8]).value(13).is_err());
+    }
+
+    #[test]
+    fn file_types_are_named() {
+        assert_eq!(file_type_name(0), Some("F32"));
+        assert_eq!(file_type_name(7), Some("Q8_0"));
+        assert_eq!(file_type_name(15), Some("Q4_K_M"));
+        assert_eq!(file_type_name(32), Some("BF16"));
+        // Q4_2 and Q4_3, long gone
+        assert_eq!(file_type_name(5), None);
+        assert_eq!(file_type_name(99), None);
+    }
+
+    #[test]
+    fn header_is_read() {
+        let mut bytes = b"GGUF".to_vec();
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        // 2 tensors, 5 key/values
+        bytes.extend_from_slice(&2u64.to_le_bytes());
+        bytes.extend_from_slice(&5u64.to_le_bytes());
+
+        let mut text = Vec::new();
+        string(&mut text, "qwen2");
+        kv(&mut bytes, "general.architecture", 8, &text);
+        let mut text = Vec::new();
+        string(&mut text, "Qwen2.5 0.5B");
+        kv(&mut bytes, "general.name", 8, &text);
+        kv(&mut bytes, "general.file_type", 4, &7u32.to_le_bytes());
+        kv(&mut bytes, "qwen2.context_length", 4, &32768u32.to_le_bytes());
+        // The vocabulary, skipped on the way to the tensors
+        let mut vocab = 8u32.to_le_bytes().to_vec();
+        vocab.extend_from_slice(&2u64.to_le_bytes());
+        string(&mut vocab, "hello");
+        string(&mut vocab, "world");
+        kv(&mut bytes, "tokenizer.ggml.tokens", 9, &vocab);
+
+        // A 2x3 and a 4 element tensor: name, dims, sizes, type, offset
+        for (name, dims) in [("a.weight", vec![2u64, 3]), ("b.bias", vec![4])] {
+            string(&mut bytes, name);
+            bytes.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+            for dim in dims {
+                bytes.extend_from_slice(&dim.to_le_bytes());
+            }
+            bytes.extend_from_slice(&[0u8; 12]);
+        }
+
+        let info = parse(Cursor::new(bytes)).unwrap();
+        assert_eq!(info.version, 3);
+        assert_eq!(info.architecture.as_deref(), Some("qwen2"));
+        assert_eq!(info.name.as_deref(), Some("Qwen2.5 0.5B"));
+        assert_eq!(info.quantization.as_deref(), Some("Q8_0"));
+        assert_eq!(info.context_length, Some(32768));
+        assert_eq!(info.chat_template, None);
+        assert_eq!(info.parameters, 10);
+    }
+
+    #[test]
+    fn other_files_are_rejected() {
+        assert!(parse(Cursor::new(b"GGML\x03\0\0\0".to_vec())).is_err());
+        assert!(parse(Cursor::new(b"GGUF\x01\0\0\0".to_vec())).is_err());
+    }
+
+    #[test]
+    fn parameters_are_formatted() {
+        assert_eq!(format_parameters(494_032_768), "494M");
+        assert_eq!(format_parameters(2_779_683_840), "2.8B");
+    }
+}
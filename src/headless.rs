@@ -0,0 +1,140 @@
+// src/headless.rs
+
+// Generic Imports
+use std::io::{IsTerminal, Read, Write};
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+// My Imports
+use crate::app::App;
+use crate::event::ClientEvent;
+use crate::backend::{BackendKind, Unauthorized};
+use crate::llama::{LlamaClient, ServerState, STARTUP_TIMEOUT};
+
+// How often we look at our llama-server while waiting
+const POLL: Duration = Duration::from_millis(500);
+
+// The question from the arguments, followed by whatever was piped in
+pub fn read_prompt(args: &[String]) -> Result<String, String> {
+    let mut prompt = args.join(" ");
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        let mut piped = String::new();
+        stdin.lock().read_to_string(&mut piped).map_err(|e| format!("Could not read stdin: {}", e))?;
+        if !piped.trim().is_empty() {
+            if !prompt.is_empty() {
+                prompt.push_str("\n\n");
+            }
+            prompt.push_str(&piped);
+        }
+    }
+    if prompt.trim().is_empty() {
+        return Err("Nothing to ask, pass a prompt or pipe one in".to_string());
+    }
+    Ok(prompt)
+}
+
+// `chalante ask`: server, model, one answer streamed to stdout, no TUI
+pub async fn ask(client: &mut LlamaClient, app: &mut App, prompt: &str) -> Result<(), String> {
+    // Startup warnings would otherwise end up in the (hidden) Output panel
+    for line in client.ter_text.drain(..) {
+        eprintln!("{}", line);
+    }
+
+    // 1. A server: the one already answering, or our own.
+    // Any answer counts, like in the TUI: a server still loading says 503
+    let answering = match client.server.is_ready().await {
+        Ok(ready) => Some(ready),
+        Err(e) if e.is::<Unauthorized>() => return Err(e.to_string()),
+        Err(_) => None,
+    };
+    if let Some(ready) = answering {
+        eprintln!("Using the server at {}", client.server.url());
+        if !ready {
+            wait_until_ready(client).await?;
+        }
+    } else if client.settings.remote.is_some() || client.settings.backend != BackendKind::Llama {
+        return Err(format!("Nothing answers at {}", client.server.url()));
+    } else {
+        eprintln!("Starting llama-server...");
+        client.start_llama();
+        loop {
+            match &client.server_state {
+                ServerState::Ready { .. } => break,
+                ServerState::Failed(reason) => return Err(format!("llama-server failed to start: {}", reason)),
+                _ => {}
+            }
+            let event = next_event(client).await?;
+            client.handle_event(event);
+        }
+    }
+
+    // 2. The model, unless the router already has it
+    let model = client.actual_model.clone();
+    let loaded = match client.server.list_models().await {
+        Ok(models) => client.loaded_ids(&models).contains(&model),
+        Err(_) => false,
+    };
+    if !loaded {
+        eprintln!("Loading {}...", model);
+        client.request_load(&model);
+        loop {
+            let event = next_event(client).await?;
+            if let ClientEvent::ModelLoaded { result: Err(e), .. } = &event {
+                return Err(format!("Could not load {}: {}", model, e));
+            }
+            let done = matches!(event, ClientEvent::ModelLoaded { .. });
+            client.handle_event(event);
+            if done {
+                break;
+            }
+        }
+    }
+    client.apply_defaults(app.catalog.get(&model));
+
+    // 3. The answer, token by token
+    client.ask(prompt);
+    let mut stdout = std::io::stdout().lock();
+    loop {
+        match next_event(client).await? {
+            ClientEvent::Token(token) => {
+                let _ = write!(stdout, "{}", token);
+                let _ = stdout.flush();
+            }
+            ClientEvent::Done => {
+                let _ = writeln!(stdout);
+                return Ok(());
+            }
+            ClientEvent::Failed(e) => return Err(e),
+            event => client.handle_event(event),
+        }
+    }
+}
+
+// Someone else's server is still loading, spawning ours would only fight it for the port
+async fn wait_until_ready(client: &LlamaClient) -> Result<(), String> {
+    eprintln!("Waiting for it to finish loading...");
+    let started = Instant::now();
+    while started.elapsed() < STARTUP_TIMEOUT {
+        tokio::time::sleep(POLL).await;
+        match client.server.is_ready().await {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            Err(e) => return Err(format!("The server at {} stopped answering: {}", client.server.url(), e)),
+        }
+    }
+    Err(format!("The server at {} is not ready after {}s", client.server.url(), STARTUP_TIMEOUT.as_secs()))
+}
+
+// Waits for the next event, giving up if our llama-server dies meanwhile
+async fn next_event(client: &mut LlamaClient) -> Result<ClientEvent, String> {
+    loop {
+        if let Ok(Some(event)) = timeout(POLL, client.rx.recv()).await {
+            return Ok(event);
+        }
+        client.check_process();
+        if let ServerState::Failed(reason) = &client.server_state {
+            return Err(format!("llama-server stopped: {}", reason));
+        }
+    }
+}
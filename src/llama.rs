@@ -1,19 +1,39 @@
 // src/llama.rs
 
 // Generic Imports
-use serde::Deserialize;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::json; 
-use reqwest::Client;
-use std::process::{Command, Stdio};
+use reqwest::{Client, Response};
+use std::process::Stdio;
+use tokio::process::{Child, ChildStderr, ChildStdout, Command};
 use ratatui::{
     text::{Line, Span},          
-    style::{Color, Style, Stylize}, };
+    style::{Color, Style}, };
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
 // For Communication
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
 
 // My Imports
 use crate::app::{ App, CurrentScreen };
+use crate::backend::{self, authorized, stream_tokens, Backend, BackendKind, BoxError, Connection, RemoteModel, Unauthorized};
+use crate::catalog::{CatalogEntry, TemplateHint};
+use crate::chat::{build_prompt, Message, Role, Sampling, STOP};
+use crate::config::Config;
 use crate::download::{install_engine, install_models};
+use crate::event::ClientEvent;
+use crate::gguf::{read_info, ModelDetails};
+use crate::logs::LogBuffer;
+use crate::memory::{self, MemoryReport};
+use crate::session::{self, ExportFormat, Session, LAST_SESSION};
+use crate::settings::{parse_url, ServerSettings, SERVER_API_KEY_ENV};
+use crate::stats::ServerStats;
+use chrono::{DateTime, Local};
 
 // Helper Structs (Just to read Models' JSON)
 #[derive(Deserialize)]
@@ -32,21 +52,227 @@ struct ModelStatus {
     value: String,
 }
 
+// Which llama-server endpoint answers the chat
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endpoint {
+    // /v1/chat/completions, the server applies the model's own chat template
+    Chat,
+    // /completion, our hand-made "User: / Assistant:" prompt
+    Completion,
+}
+
+// What we know about the llama-server we launched
+#[derive(Clone, PartialEq)]
+pub enum ServerState {
+    Stopped,
+    // Spawned, /health doesn't answer "ok" yet
+    Loading { pid: u32 },
+    Ready { pid: u32 },
+    Stopping,
+    Failed(String),
+    // Looking for a server that is already running
+    Connecting,
+    // Somebody else's llama-server, we only talk to it
+    Connected { url: String },
+}
+
+impl ServerState {
+    pub fn label(&self) -> String {
+        match self {
+            ServerState::Stopped => "stopped".to_string(),
+            ServerState::Loading { pid } => format!("loading (pid {})", pid),
+            ServerState::Ready { pid } => format!("ready (pid {})", pid),
+            ServerState::Stopping => "stopping".to_string(),
+            ServerState::Failed(reason) => format!("failed: {}", reason),
+            ServerState::Connecting => "connecting".to_string(),
+            ServerState::Connected { url } => format!("connected to {}", url),
+        }
+    }
+}
+
+// How long llama-server gets to answer /health after spawning
+const STARTUP_POLL: Duration = Duration::from_millis(500);
+pub const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+// How long it gets to stop its models after SIGTERM
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+// How often we look at the memory our llama-server holds
+const MEMORY_EVERY: Duration = Duration::from_secs(1);
+// How often the Stats panel asks the server
+const STATS_EVERY: Duration = Duration::from_secs(2);
+// How often the model list asks /models, faster while a model is on its way in or out
+const STATUS_EVERY: Duration = Duration::from_secs(5);
+const STATUS_SETTLING_EVERY: Duration = Duration::from_secs(1);
+// Statuses that stay put until someone loads or unloads
+const FINAL_STATUSES: [&str; 3] = ["loaded", "unloaded", "failed"];
+
+// The HTTP side of llama-server (router mode)
+pub struct LlamaServer {
+    pub connection: Connection,
+}
+
+#[async_trait]
+impl Backend for LlamaServer {
+    fn url(&self) -> &str {
+        &self.connection.url
+    }
+
+    // GET Requests
+    async fn health(&self) -> Result<String, BoxError> {
+        let res: serde_json::Value = authorized(self.connection.get("health").send().await?)?
+            .json()
+            .await?;
+
+        Ok(res.to_string())
+    }
+
+    // 200 once the server can take requests, 503 while it is still loading
+    async fn is_ready(&self) -> Result<bool, BoxError> {
+        let res = authorized(self.connection.get("health").send().await?)?;
+
+        Ok(res.status().is_success())
+    }
+
+    async fn list_models(&self) -> Result<Vec<RemoteModel>, BoxError> {
+        let list: ModelList = authorized(self.connection.get("models").send().await?)?
+            .json()
+            .await?;
+
+        Ok(list.data
+            .into_iter()
+            .map(|m| RemoteModel { id: m.id, status: Some(m.status.value) })
+            .collect())
+    }
+
+    // POST Requests
+    async fn load_model(&self, model: &str) -> Result<String, BoxError> {
+        self.router("models/load", model).await
+    }
+
+    async fn unload_model(&self, model: &str) -> Result<String, BoxError> {
+        self.router("models/unload", model).await
+    }
+
+    // Our hand-made "User: / Assistant:" prompt
+    async fn complete(&self, model: &str, prompt: &str, sampling: &Sampling, tx: &UnboundedSender<ClientEvent>) -> Result<(), BoxError> {
+        let body = json!({
+            "model": model,
+            "prompt": prompt,
+            "n_predict": sampling.n_predict,
+            "temperature": sampling.temperature,
+            "stop": STOP,
+            "cache_prompt": true,
+            "stream": true
+        });
+        stream_tokens(self.connection.post("completion"), body, tx).await
+    }
+
+    // The server applies the model's own chat template
+    async fn chat(&self, model: &str, messages: &[Message], sampling: &Sampling, tx: &UnboundedSender<ClientEvent>) -> Result<(), BoxError> {
+        let body = json!({
+            "model": model,
+            "messages": messages,
+            "max_tokens": sampling.n_predict,
+            "temperature": sampling.temperature,
+            "cache_prompt": true,
+            "stream": true
+        });
+        stream_tokens(self.connection.post("v1/chat/completions"), body, tx).await
+    }
+
+    // All three at once, any of them may be missing (no --metrics, older server)
+    async fn stats(&self, model: &str) -> Result<ServerStats, BoxError> {
+        let (props, slots, metrics) = tokio::join!(
+            self.optional("props", model),
+            self.optional("slots", model),
+            self.optional("metrics", model),
+        );
+        let (props, slots, metrics) = (props?, slots?, metrics?);
+        if props.is_none() && slots.is_none() && metrics.is_none() {
+            return Err("the server shares no stats".into());
+        }
+
+        let mut stats = ServerStats::default();
+        if let Some(res) = props {
+            stats.read_props(&res.json().await?);
+        }
+        if let Some(res) = slots {
+            stats.read_slots(&res.json().await?);
+        }
+        if let Some(res) = metrics {
+            stats.read_metrics(&res.text().await?);
+        }
+        Ok(stats)
+    }
+}
+
+impl LlamaServer {
+    // GET for an endpoint the server may have disabled, None if it did.
+    // In router mode "model" picks whose numbers we get.
+    async fn optional(&self, path: &str, model: &str) -> Result<Option<Response>, BoxError> {
+        let res = authorized(self.connection.get(path).query(&[("model", model)]).send().await?)?;
+        Ok(res.status().is_success().then_some(res))
+    }
+
+    // The router answers {"success": true} or an error object
+    async fn router(&self, path: &str, model: &str) -> Result<String, BoxError> {
+        let body = json!({
+            "model": model
+        });
+        let res: serde_json::Value = authorized(self.connection.post(path).json(&body).send().await?)?
+            .json()
+            .await?;
+
+        if let Some(error) = res["error"]["message"].as_str() {
+            return Err(error.into());
+        }
+        let content = res["success"].to_string();
+
+        Ok(content)
+    }
+}
 
 // LlamaClient Struct
 pub struct LlamaClient {
-    pub client: Client,
-    pub url: String,
+    pub server: Arc<dyn Backend>,
+    // Shared by the backend and the model downloads
+    pub http: Client,
     pub user_text: String,
     pub ter_text: Vec<String>,
     pub history: Vec<Line<'static>>,
-    pub models: String,
-    pub engine_on: bool,
+    pub messages: Vec<Message>,
+    pub sampling: Sampling,
+    pub started: DateTime<Local>,
+    // Our own llama-server, nobody else's
+    pub process: Option<Child>,
+    pub server_state: ServerState,
+    pub server_log: LogBuffer,
+    // Only one "install models" at a time, they would share the .part files
+    pub installing: bool,
+    pub memory: MemoryReport,
+    memory_checked: Instant,
+    // None until the first poll answers
+    pub stats: Option<Result<ServerStats, String>>,
+    stats_checked: Instant,
+    stats_pending: bool,
+    status_checked: Instant,
+    status_pending: bool,
+    pub settings: ServerSettings,
+    // What was loaded at startup, "save config" writes it back updated
+    pub config: Config,
+    pub config_path: Option<PathBuf>,
     // For Communication
-    pub tx: UnboundedSender<String>,
-    pub rx: UnboundedReceiver<String>,
+    pub tx: UnboundedSender<ClientEvent>,
+    pub rx: UnboundedReceiver<ClientEvent>,
+    // For Streaming Answers
+    pub generating: bool,
+    pub generation: Option<JoinHandle<()>>,
+    pub endpoint: Endpoint,
     // To get a Hold of the Server
     pub actual_model: String,
+    pub loading_model: Option<String>,
+    // --start-server loads actual_model as soon as the server is up
+    pub load_when_ready: bool,
 }
 
 // LLamaClient Methods
@@ -55,197 +281,928 @@ impl LlamaClient {
     pub fn new() -> Self {
         // For Communication
         let (tx, rx) = unbounded_channel();
+        let http = Client::new();
         Self {
-            client: Client::new(),
-            url: format!("http://127.0.0.1:11343"),
+            server: backend::connect(BackendKind::Llama, http.clone(), ServerSettings::default().url(), None),
+            http,
             user_text: String::new(),
             ter_text: Vec::new(),
             history: Vec::new(),
-            models: String::new(),
-            engine_on: false,
+            messages: Vec::new(),
+            sampling: Sampling::default(),
+            started: Local::now(),
+            process: None,
+            server_state: ServerState::Stopped,
+            server_log: LogBuffer::new(),
+            installing: false,
+            memory: MemoryReport::default(),
+            memory_checked: Instant::now(),
+            stats: None,
+            stats_checked: Instant::now(),
+            stats_pending: false,
+            status_checked: Instant::now(),
+            status_pending: false,
+            settings: ServerSettings::default(),
+            config: Config::default(),
+            config_path: None,
             tx,
             rx,
+            generating: false,
+            generation: None,
+            endpoint: Endpoint::Chat,
             actual_model: String::from("qwen"),
+            loading_model: None,
+            load_when_ready: false,
         }
     }
 
     // Once installed, Starts Router Mode
-    pub async fn start_llama(&mut self) {
-        let child = Command::new("llama.cpp/build/bin/llama-server")
-        .args(["--models-dir", "models", "--port", "11343", "--log-disable"])
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .expect("Failed to start llama-server");
+    // Readiness is reported later through ServerReady / ServerFailed
+    pub fn start_llama(&mut self) {
+        self.ter_text.clear();
+        self.server_log.clear();
+
+        // Settings changed since the last start apply now
+        self.use_url(self.settings.url());
+        let mut command = Command::new(&self.settings.binary);
+        if let Some(key) = self.settings.api_key() {
+            command.env(SERVER_API_KEY_ENV, key);
+        }
+        let spawned = command
+        .args(self.settings.args())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Never outlive Chalante, even if we panic
+        .kill_on_drop(true)
+        .spawn();
+
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                self.server_state = ServerState::Failed(e.to_string());
+                self.ter_text.push(format!("Error: Could not start llama-server: {}", e));
+                self.ter_text.push("Did you run \"install engine\"?".to_string());
+                return;
+            }
+        };
+        let Some(pid) = child.id() else {
+            self.server_state = ServerState::Failed("exited right away".to_string());
+            return;
+        };
+
+        // Its output comes back as ServerLog events
+        if let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) {
+            tokio::spawn(forward_logs(stdout, stderr, self.tx.clone()));
+        }
 
-        //self.engine_on = true;
+        self.server_state = ServerState::Loading { pid };
+        self.process = Some(child);
+        self.ter_text.push(format!("Llama Server Loading (pid {})...", pid));
+        self.watch_startup(pid);
     }
 
-    // GET Requests
-    pub async fn get_health(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let res: serde_json::Value = self.client.get(format!("{}/health", &self.url))
-            .send()
-            .await?
-            .json()
-            .await?;
+    // Polls /health until the server says it's ready
+    fn watch_startup(&self, pid: u32) {
+        let server = self.server.clone();
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let started = Instant::now();
+            while started.elapsed() < STARTUP_TIMEOUT {
+                tokio::time::sleep(STARTUP_POLL).await;
+                // Refused connections just mean it's not listening yet
+                if let Ok(true) = server.is_ready().await {
+                    let _ = tx.send(ClientEvent::ServerReady { pid });
+                    return;
+                }
+            }
+            let reason = format!("not ready after {}s", STARTUP_TIMEOUT.as_secs());
+            let _ = tx.send(ClientEvent::ServerFailed { pid, reason });
+        });
+    }
 
-        Ok(res.to_string())
+    // "start server": reuses a server that already answers, spawns ours otherwise
+    pub fn start_server(&mut self) {
+        self.ter_text.clear();
+        match &self.server_state {
+            _ if self.process.is_some() => self.ter_text.push("Llama Server Already Running".to_string()),
+            ServerState::Connecting => self.ter_text.push("Still looking for a server, wait a moment".to_string()),
+            ServerState::Connected { url } => {
+                self.ter_text.push(format!("Already connected to {} (\"disconnect\" first)", url));
+            }
+            // A remote server is never ours to start
+            _ => match self.settings.remote.clone() {
+                Some(remote) => self.probe(remote, false),
+                // Only llama-server is ours to launch
+                None => self.probe(self.settings.url(), self.settings.backend == BackendKind::Llama),
+            },
+        }
     }
 
-    pub async fn get_models(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let res: serde_json::Value = self.client.get(format!("{}/models", &self.url))
-            .send()    
-            .await?
-            .json()
-            .await?;
-        
-        Ok(res.to_string())
+    // "connect <url>": talk to a llama-server somebody else runs
+    pub fn connect(&mut self, url: &str) {
+        self.ter_text.clear();
+        if self.process.is_some() {
+            self.ter_text.push("Stop our llama-server first (\"stop server\")".to_string());
+            return;
+        }
+        match parse_url(url) {
+            Ok(url) => {
+                self.settings.remote = Some(url.clone());
+                self.probe(url, false);
+            }
+            Err(e) => self.ter_text.push(format!("Error: {}", e)),
+        }
+    }
+
+    // Back to launching our own
+    pub fn disconnect(&mut self) {
+        self.ter_text.clear();
+        self.settings.remote = None;
+        self.use_url(self.settings.url());
+        if matches!(self.server_state, ServerState::Connected { .. } | ServerState::Connecting) {
+            self.server_state = ServerState::Stopped;
+        }
+        self.ter_text.push(format!("Disconnected, \"start server\" will use {}", self.server.url()));
+    }
+
+    // Points every request at url, through the configured backend
+    pub fn use_url(&mut self, url: String) {
+        self.server = backend::connect(self.settings.backend, self.http.clone(), url, self.settings.api_key());
+    }
+
+    // Asks url for /health in the background, ServerProbed has the answer
+    fn probe(&mut self, url: String, start: bool) {
+        self.ter_text.push(format!("Looking for a llama-server at {}...", url));
+        self.use_url(url.clone());
+        self.server_state = ServerState::Connecting;
+        let server = self.server.clone();
+        self.dispatch(async move {
+            // Any answer counts, a server still loading says 503 (and a locked one 401)
+            let answering = match server.is_ready().await {
+                Ok(_) => true,
+                Err(e) => e.is::<Unauthorized>(),
+            };
+            ClientEvent::ServerProbed { url, answering, start }
+        });
+    }
+
+    // Stops our llama-server in the background, ServerStopped arrives once it's gone
+    pub fn stop_llama(&mut self, restart: bool) -> bool {
+        let Some(child) = self.process.take() else { return false };
+        self.server_state = ServerState::Stopping;
+
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            terminate(child).await;
+            let _ = tx.send(ClientEvent::ServerStopped { restart });
+        });
+        true
+    }
+
+    // For exiting: stop and reap it before the runtime goes away
+    pub async fn shutdown(&mut self) {
+        if let Some(child) = self.process.take() {
+            terminate(child).await;
+        }
     }
 
-    pub fn readable(&self, raw_json: &str) -> String {
-        // 1. Parse the raw string into our structs
-        let parsed: Result<ModelList, _> = serde_json::from_str(raw_json);
+    // Notices a llama-server that died on its own
+    pub fn check_process(&mut self) {
+        let Some(child) = self.process.as_mut() else { return };
+        if let Ok(Some(status)) = child.try_wait() {
+            self.process = None;
+            let during_startup = matches!(self.server_state, ServerState::Loading { .. });
+            self.server_state = ServerState::Failed(format!("exited ({})", status));
 
-        match parsed {
-            Ok(list) => {
-                // 2. Map each entry into a clean string line
-                let lines: Vec<String> = list.data
-                    .iter()
-                    .map(|m| format!("- {}: [{}]", m.id, m.status.value))
-                    .collect();
+            self.ter_text.clear();
+            if during_startup {
+                self.ter_text.push(format!("Error: llama-server died while starting ({})", status));
+            } else {
+                self.ter_text.push(format!("Error: llama-server exited ({})", status));
+            }
+            // The last words usually say why (port in use, bad model...)
+            let last_words: Vec<String> = self.server_log.tail(5).map(|line| line.text.clone()).collect();
+            self.ter_text.extend(last_words);
+        }
+    }
+
+    // Only the ones the server has in memory right now
+    pub fn loaded_ids(&self, models: &[RemoteModel]) -> Vec<String> {
+        models.iter()
+            .filter(|m| m.status.as_deref() == Some("loaded"))
+            .map(|m| m.id.clone())
+            .collect()
+    }
+
+    pub fn readable(&self, models: &[RemoteModel]) -> String {
+        // One clean line per model, with its status if the server tracks it
+        let lines: Vec<String> = models
+            .iter()
+            .map(|m| match &m.status {
+                Some(status) => format!("- {}: [{}]", m.id, status),
+                None => format!("- {}", m.id),
+            })
+            .collect();
+
+        if lines.is_empty() {
+            "No models found.".to_string()
+        } else {
+            lines.join("\n")
+        }
+    }
+
+    pub fn ask(&mut self, prompt: &str) {
+        self.messages.push(Message::new(Role::User, prompt));
 
-                // 3. Join them with newlines
-                if lines.is_empty() {
-                    "No models found.".to_string()
+        // The whole conversation, not just the last question
+        let messages = self.messages.clone();
+        let model = self.actual_model.clone();
+        let sampling = self.sampling.clone();
+        let endpoint = self.endpoint;
+
+        // Empty AI turn, the tokens get appended to it as they arrive
+        let answer = Message::new(Role::Assistant, "");
+        self.push_history(&answer);
+        self.messages.push(answer);
+        self.generating = true;
+
+        // Spawn it so it doesn't block the TUI!
+        let server = self.server.clone();
+        let tx = self.tx.clone();
+        self.generation = Some(tokio::spawn(async move {
+            let result = match endpoint {
+                Endpoint::Chat => server.chat(&model, &messages, &sampling, &tx).await,
+                Endpoint::Completion => server.complete(&model, &build_prompt(&messages), &sampling, &tx).await,
+            };
+            match result {
+                Ok(()) => { let _ = tx.send(ClientEvent::Done); }
+                Err(e) => { let _ = tx.send(ClientEvent::Failed(e.to_string())); }
+            }
+        }));
+    }
+
+    // Stops the answer being generated, keeping what already arrived
+    pub fn cancel(&mut self, app: &mut App) {
+        if !self.generating {
+            return;
+        }
+        if let Some(generation) = self.generation.take() {
+            generation.abort();
+        }
+        // Tokens already on their way still belong to the answer,
+        // and downloads or loads queued behind them still need the App
+        self.handle_events(app);
+        // It finished while we were draining
+        if !self.generating {
+            return;
+        }
+        self.generating = false;
+
+        if let Some(line) = self.history.last_mut() {
+            line.spans.push(Span::styled(" [interrupted]", Style::default().fg(Color::DarkGray)));
+        }
+        // Nothing arrived, so forget the turn like a failed one
+        if self.messages.last().is_some_and(|m| m.role == Role::Assistant && m.content.is_empty()) {
+            self.messages.pop();
+            self.messages.pop();
+        }
+        self.ter_text.push("Generation interrupted".to_string());
+    }
+
+    // The current conversation, ready to be written to disk
+    pub fn to_session(&self, name: &str) -> Session {
+        Session {
+            name: name.to_string(),
+            model: self.actual_model.clone(),
+            created: self.started,
+            updated: Local::now(),
+            sampling: self.sampling.clone(),
+            messages: self.messages.clone(),
+        }
+    }
+
+    // Replaces the current conversation with a saved one
+    pub fn restore(&mut self, session: Session) {
+        self.actual_model = session.model;
+        self.started = session.created;
+        self.sampling = session.sampling;
+        self.messages = session.messages;
+
+        self.history.clear();
+        for message in self.messages.clone() {
+            self.push_history(&message);
+        }
+    }
+
+    pub fn save_chat(&mut self, name: &str) {
+        self.ter_text.clear();
+        if !session::valid_name(name) {
+            self.ter_text.push("Error: Use letters, numbers, '-' or '_' for chat names".to_string());
+            return;
+        }
+        match self.to_session(name).save() {
+            Ok(path) => self.ter_text.push(format!("Chat saved to {}", path.display())),
+            Err(e) => self.ter_text.push(format!("Error: Could not save chat: {}", e)),
+        }
+    }
+
+    pub fn load_chat(&mut self, app: &mut App, name: &str) {
+        self.ter_text.clear();
+        if self.generating {
+            self.ter_text.push("Still answering, wait or press Ctrl+C".to_string());
+            return;
+        }
+        if !session::valid_name(name) {
+            self.ter_text.push("Error: Use letters, numbers, '-' or '_' for chat names".to_string());
+            return;
+        }
+        match Session::load(name) {
+            Ok(session) => {
+                self.restore(session);
+                app.select_model(&self.actual_model);
+                self.ter_text.push(format!("Chat {} loaded ({})", name, self.actual_model));
+                // The chat remembers its model, the server may not have it in memory
+                if self.settings.backend == BackendKind::Llama
+                    && app.model_status.get(&self.actual_model).is_none_or(|status| status != "loaded") {
+                    self.ter_text.push(format!("{} is not loaded, press [TAB] or type \"load model\"", self.actual_model));
+                }
+            }
+            Err(e) => self.ter_text.push(format!("Error: Could not load chat {}: {}", name, e)),
+        }
+    }
+
+    // "export chat <path> [md|txt|json]"
+    pub fn export_chat(&mut self, args: &str) {
+        self.ter_text.clear();
+        let (path, format) = match args.rsplit_once(' ') {
+            Some((path, format)) if ExportFormat::parse(format).is_some() => {
+                (Path::new(path.trim()), ExportFormat::parse(format))
+            }
+            _ => (Path::new(args), None),
+        };
+        if path.as_os_str().is_empty() {
+            self.ter_text.push("Usage: export chat <path> [md|txt|json]".to_string());
+            return;
+        }
+        let format = format.unwrap_or_else(|| ExportFormat::from_path(path));
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+
+        match self.to_session(&name).export(path, format) {
+            Ok(()) => self.ter_text.push(format!("Chat exported to {}", path.display())),
+            Err(e) => self.ter_text.push(format!("Error: Could not export chat: {}", e)),
+        }
+    }
+
+    // Called on exit and launch, so closing Chalante doesn't lose the chat
+    pub fn save_last(&self) {
+        if !self.messages.is_empty() {
+            let _ = self.to_session(LAST_SESSION).save();
+        }
+    }
+
+    // The config's [sampling] wins over the one saved with it
+    pub fn restore_last(&mut self) {
+        if let Ok(session) = Session::load(LAST_SESSION) {
+            let sampling = self.sampling.clone();
+            self.restore(session);
+            self.sampling = sampling;
+        }
+    }
+
+    // Runs a request in its own task, its result comes back as an event
+    fn dispatch<F>(&self, task: F)
+    where
+        F: Future<Output = ClientEvent> + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send(task.await);
+        });
+    }
+
+    // This method should be called in your main loop every "frame" or "tick"
+    pub fn handle_events(&mut self, app: &mut App) {
+        while let Ok(event) = self.rx.try_recv() {
+            match &event {
+                ClientEvent::Models(Ok(models)) | ClientEvent::ModelStates(models) => app.update_status(models),
+                ClientEvent::ServerStopped { .. } => app.model_status.clear(),
+                ClientEvent::Download(progress) => app.update_download(progress.clone()),
+                // The highlight follows the model actually in use
+                ClientEvent::ModelLoaded { model, result: Ok(_) } => {
+                    app.select_model(model);
+                    self.apply_defaults(app.catalog.get(model));
+                }
+                ClientEvent::ModelInfo { model, result } => {
+                    let details = match result {
+                        Ok(info) => ModelDetails::Ready(info.clone()),
+                        Err(e) => ModelDetails::Failed(e.clone()),
+                    };
+                    app.model_details.insert(model.clone(), details);
+                }
+                _ => {}
+            }
+            self.handle_event(event);
+        }
+        self.check_process();
+        self.request_details(app);
+        self.check_memory();
+        self.check_status(app);
+        self.check_stats(app);
+    }
+
+    // The router loads, evicts and fails models on its own time, so /models is polled
+    fn check_status(&mut self, app: &App) {
+        if self.settings.backend != BackendKind::Llama
+            || !matches!(self.server_state, ServerState::Ready { .. } | ServerState::Connected { .. })
+            || self.status_pending {
+            return;
+        }
+        let settling = self.loading_model.is_some()
+            || app.model_status.values().any(|status| !FINAL_STATUSES.contains(&status.as_str()));
+        let every = if settling { STATUS_SETTLING_EVERY } else { STATUS_EVERY };
+        if self.status_checked.elapsed() >= every {
+            self.refresh_status();
+        }
+    }
+
+    // Polls the server while the Chat Area shows its stats, one request at a time
+    fn check_stats(&mut self, app: &App) {
+        if !matches!(self.server_state, ServerState::Ready { .. } | ServerState::Connected { .. }) {
+            self.stats = None;
+            return;
+        }
+        if app.current_screen != CurrentScreen::Chat || self.stats_pending || self.stats_checked.elapsed() < STATS_EVERY {
+            return;
+        }
+        // Asking the router about a model it hasn't loaded would load it,
+        // so a model that doesn't look loaded gets its status checked again first
+        if app.model_status.get(&self.actual_model).is_some_and(|status| status != "loaded") {
+            self.stats = None;
+            if !self.status_pending && self.status_checked.elapsed() >= STATS_EVERY {
+                self.refresh_status();
+            }
+            return;
+        }
+        self.stats_pending = true;
+        self.stats_checked = Instant::now();
+
+        let server = self.server.clone();
+        let model = self.actual_model.clone();
+        self.dispatch(async move {
+            ClientEvent::Stats(server.stats(&model).await.map_err(|e| e.to_string()))
+        });
+    }
+
+    // Measures our llama-server once in a while (in the background, /proc can be slow)
+    fn check_memory(&mut self) {
+        let Some(pid) = self.process.as_ref().and_then(|child| child.id()) else {
+            self.memory = MemoryReport::default();
+            return;
+        };
+        if self.memory_checked.elapsed() < MEMORY_EVERY {
+            return;
+        }
+        self.memory_checked = Instant::now();
+        self.dispatch(async move {
+            let report = tokio::task::spawn_blocking(move || memory::report(pid)).await.unwrap_or_default();
+            ClientEvent::Memory(report)
+        });
+    }
+
+    // Quietly asks /models for fresh statuses
+    fn refresh_status(&mut self) {
+        self.status_pending = true;
+        self.status_checked = Instant::now();
+        let server = self.server.clone();
+        self.dispatch(async move {
+            ClientEvent::ModelStates(server.list_models().await.unwrap_or_default())
+        });
+    }
+
+    // Reads the highlighted model's GGUF header in the background (once)
+    fn request_details(&self, app: &mut App) {
+        let Some(model) = app.selected_model().cloned() else { return };
+        if !app.installed.contains(&model) || app.model_details.contains_key(&model) {
+            return;
+        }
+        app.model_details.insert(model.clone(), ModelDetails::Loading);
+
+        let path = app.model_path(&model);
+        self.dispatch(async move {
+            let result = match tokio::task::spawn_blocking(move || read_info(&path)).await {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            ClientEvent::ModelInfo { model, result }
+        });
+    }
+
+    pub fn handle_event(&mut self, event: ClientEvent) {
+        match event {
+            ClientEvent::Log(msg) => {
+                self.ter_text.push(msg);
+
+                // Keep only the last 10 lines
+                if self.ter_text.len() > 10 {
+                    self.ter_text.remove(0);
+                }
+            }
+            ClientEvent::Output(msg) => {
+                self.ter_text.clear(); // Clear old logs
+                self.ter_text.push(msg); // Add the new one
+            }
+            ClientEvent::Models(result) => {
+                self.ter_text.clear();
+                match result {
+                    // readable returns a String, so we push it
+                    Ok(models) => self.ter_text.push(self.readable(&models)),
+                    Err(e) => self.ter_text.push(format!("Error: Could not retrieve models ({})", e)),
+                }
+            }
+            ClientEvent::ModelUnloaded { model, result } => {
+                self.ter_text.clear();
+                match result {
+                    Ok(_) => self.ter_text.push(format!("Model Unloaded: {}", model)),
+                    Err(e) => self.ter_text.push(format!("Error: Could not unload {}: {}", model, e)),
+                }
+                self.refresh_status();
+            }
+            ClientEvent::ModelStates(_) => self.status_pending = false,
+            ClientEvent::Stats(result) => {
+                self.stats_pending = false;
+                self.stats = Some(result);
+            }
+            ClientEvent::Memory(report) => {
+                // A report that arrives after the server is gone is stale
+                if self.process.is_some() {
+                    self.memory = report;
+                }
+            }
+            ClientEvent::ModelLoaded { model, result } => {
+                self.refresh_status();
+                self.loading_model = None;
+                self.ter_text.clear();
+                match result {
+                    Ok(res) => {
+                        self.ter_text.push(format!("Model Loaded: {} ({})", model, res));
+                        self.actual_model = model;
+                    }
+                    Err(e) => self.ter_text.push(format!("Error: Could not load {}: {}", model, e)),
+                }
+            }
+            // Leftovers from a cancelled answer are dropped
+            ClientEvent::Token(token) if self.generating => self.push_token(&token),
+            ClientEvent::Done if self.generating => {
+                self.generating = false;
+                self.generation = None;
+            }
+            ClientEvent::Failed(e) if self.generating => {
+                self.generating = false;
+                self.generation = None;
+                self.ter_text.push(format!("Error: {}", e));
+                // A failed turn with no answer would confuse the next prompt
+                if self.messages.last().is_some_and(|m| m.role == Role::Assistant && m.content.is_empty()) {
+                    self.messages.pop();
+                    self.messages.pop();
+                }
+            }
+            ClientEvent::Token(_) | ClientEvent::Done | ClientEvent::Failed(_) => {}
+            ClientEvent::ServerLog(line) => self.server_log.push(line),
+            // Only a server that is still starting (and alive) can become ready
+            // From a watch_startup of an older server, if the pid doesn't match
+            ClientEvent::ServerReady { pid } => {
+                if self.server_state == (ServerState::Loading { pid }) {
+                    self.check_process();
+                    if self.process.is_some() {
+                        self.server_state = ServerState::Ready { pid };
+                        self.ter_text.clear();
+                        self.ter_text.push(format!("Llama Server Ready (pid {})", pid));
+                        self.refresh_status();
+                        if self.load_when_ready {
+                            self.load_when_ready = false;
+                            self.request_load(&self.actual_model.clone());
+                        }
+                    }
+                }
+            }
+            ClientEvent::ServerFailed { pid, reason } => {
+                if self.server_state == (ServerState::Loading { pid }) {
+                    // Stuck, so it goes: "start server" can try again
+                    if let Some(child) = self.process.take() {
+                        tokio::spawn(terminate(child));
+                    }
+                    self.ter_text.clear();
+                    self.ter_text.push(format!("Error: llama-server failed to start: {}", reason));
+                    self.server_state = ServerState::Failed(reason);
+                }
+            }
+            // Ignored if something else happened to the server meanwhile
+            ClientEvent::ServerProbed { url, answering, start } => {
+                if self.server_state != ServerState::Connecting {
+                    return;
+                }
+                self.ter_text.clear();
+                if answering {
+                    self.ter_text.push(format!("Using the llama-server already answering at {}", url));
+                    self.server_state = ServerState::Connected { url };
+                    self.refresh_status();
+                    if self.load_when_ready {
+                        self.load_when_ready = false;
+                        self.request_load(&self.actual_model.clone());
+                    }
+                } else if start {
+                    self.start_llama();
                 } else {
-                    lines.join("\n")
+                    self.server_state = ServerState::Stopped;
+                    self.ter_text.push(format!("Error: Nothing answers at {}", url));
                 }
             }
-            Err(_) => "Error: Could not parse model list.".to_string(),
+            ClientEvent::ServerStopped { restart } => {
+                self.server_state = ServerState::Stopped;
+                self.ter_text.clear();
+                if restart {
+                    self.start_llama();
+                } else {
+                    self.ter_text.push("Llama Server Stopped".to_string());
+                }
+            }
+            ClientEvent::InstallFinished => self.installing = false,
+            // Live in App, see handle_events
+            ClientEvent::Download(_) | ClientEvent::ModelInfo { .. } => {}
         }
     }
 
-    // POST Requests
-    pub async fn load_model(&mut self, model: &str) -> Result<String, Box<dyn std::error::Error>> {
-        self.actual_model = model.to_string();
-        let body = json!({
-            "model": model
+    // Downloads the catalog in the background, unless it's already at it
+    fn install_catalog(&mut self, app: &App, verify: bool) {
+        if self.installing {
+            self.ter_text.push("Models are already being installed, wait for them to finish".to_string());
+            return;
+        }
+        self.installing = true;
+
+        let client = self.http.clone();
+        let models = app.catalog.models.clone();
+        let dir = app.models_dir.clone();
+        let tx = self.tx.clone();
+        // Spawn it so it doesn't block the TUI!
+        tokio::spawn(async move {
+            install_models(client, models, dir, verify, tx.clone()).await;
+            let _ = tx.send(ClientEvent::InstallFinished);
         });
-        let res: serde_json::Value = self.client.post(format!("{}/models/load", &self.url))
-            .json(&body)
-            .send()
-            .await?
-            .json()
-            .await?;
+    }
 
-        let content = res["success"]
-            .as_str()
-            .ok_or("Failed to get content")?
-            .to_string();
+    // Takes the settings from a config file, before anything else runs
+    pub fn apply_config(&mut self, app: &mut App, config: Config) {
+        self.settings = config.server.clone();
+        self.use_url(self.settings.server_url());
+        self.sampling = config.sampling.clone();
+        self.endpoint = config.ui.endpoint;
+        self.actual_model = config.ui.model.clone();
+        app.paths = config.paths.clone();
+        app.current_screen = config.ui.screen;
+        app.set_models_dir(self.settings.models_dir.clone());
+        self.config = config;
+    }
 
-        
-        Ok(content)
+    // The loaded config with whatever was changed in the app since
+    pub fn current_config(&self, app: &App) -> Config {
+        let mut config = self.config.clone();
+        config.server = self.settings.clone();
+        config.sampling = self.sampling.clone();
+        config.ui.endpoint = self.endpoint;
+        config.ui.model = self.actual_model.clone();
+        config.paths = app.paths.clone();
+        config
     }
 
-    pub async fn ask(&self, prompt: &str) -> Result<String, Box<dyn std::error::Error>> {
-        // Formulation
-        let body = json!({
-            "model": self.actual_model,
-            "prompt": format!("\nUser: {}\nAssistant:", prompt),
-            "n_predict": 200,
-            "temperature": 0.2,
-            "stop": ["User:", "Assistant:", "\nUser:", "<|im_end|>", "<|endoftext|>"],
-            "cache_prompt": true
+    pub fn save_config(&mut self, app: &App) {
+        self.ter_text.clear();
+        let Some(path) = self.config_path.clone() else {
+            self.ter_text.push("Error: No config directory on this system".to_string());
+            return;
+        };
+        let config = self.current_config(app);
+        match config.save(&path) {
+            Ok(()) => {
+                self.config = config;
+                self.ter_text.push(format!("Config saved to {}", path.display()));
+            }
+            Err(e) => self.ter_text.push(format!("Error: Could not save config: {}", e)),
+        }
+    }
+
+    // "set <key> <value>", used on the next "start server"
+    pub fn set_setting(&mut self, app: &mut App, args: &str) {
+        self.ter_text.clear();
+        let Some((key, value)) = args.trim().split_once(' ') else {
+            self.ter_text.push("Usage: set <key> <value> (see \"show settings\")".to_string());
+            return;
+        };
+        match self.settings.set(key, value.trim()) {
+            Ok(()) => {
+                // The model list follows the folder the server will use
+                if key == "models_dir" {
+                    app.set_models_dir(self.settings.models_dir.clone());
+                }
+                // Needed right away, not just by the next server
+                if key == "api_key" || key == "backend" {
+                    self.use_url(self.server.url().to_string());
+                }
+                let value = if key == "api_key" { "(hidden)" } else { value.trim() };
+                self.ter_text.push(format!("{} = {} (applied on the next \"start server\")", key, value));
+            }
+            Err(e) => self.ter_text.push(format!("Error: {}", e)),
+        }
+    }
+
+    // Per-model settings from the catalog, applied when the model is loaded
+    pub fn apply_defaults(&mut self, entry: Option<&CatalogEntry>) {
+        let Some(entry) = entry else { return };
+        match entry.template {
+            Some(TemplateHint::Embedded) => self.endpoint = Endpoint::Chat,
+            Some(TemplateHint::Raw) => self.endpoint = Endpoint::Completion,
+            None => {}
+        }
+        if let Some(max_tokens) = entry.max_tokens {
+            self.sampling.n_predict = max_tokens;
+        }
+        if let Some(temperature) = entry.temperature {
+            self.sampling.temperature = temperature;
+        }
+    }
+
+    // Commands shared by both pages, true if the text was one of them
+    fn common_command(&mut self, app: &mut App, text: &str) -> bool {
+        if let Some(model) = text.strip_prefix("load model ") {
+            self.request_load(model.trim());
+            return true;
+        }
+        if let Some(model) = text.strip_prefix("unload model ") {
+            self.request_unload(model.trim());
+            return true;
+        }
+        match text {
+            "load model" => self.request_load(&self.actual_model.clone()),
+            "unload model" => self.request_unload(&self.actual_model.clone()),
+            "get health" => {
+                let server = self.server.clone();
+                self.dispatch(async move {
+                    match server.health().await {
+                        Ok(health) => ClientEvent::Output(health),
+                        Err(e) if e.is::<Unauthorized>() => ClientEvent::Output(format!("Error: {}", e)),
+                        Err(_) => ClientEvent::Output("Error: Server unreachable".to_string()),
+                    }
+                });
+            },
+            "list models" => {
+                app.refresh_models();
+                let server = self.server.clone();
+                self.dispatch(async move {
+                    ClientEvent::Models(server.list_models().await.map_err(|e| e.to_string()))
+                });
+            },
+            "list catalog" => {
+                self.ter_text.clear();
+                if app.catalog.models.is_empty() {
+                    self.ter_text.push("The catalog is empty.".to_string());
+                }
+                for entry in &app.catalog.models {
+                    let installed = if app.installed.contains(&entry.name) { " [installed]" } else { "" };
+                    self.ter_text.push(format!("{}{}", entry.summary(), installed));
+                }
+            },
+            "use chat endpoint" => {
+                self.endpoint = Endpoint::Chat;
+                self.ter_text.clear();
+                self.ter_text.push("Using /v1/chat/completions (model's chat template)".to_string());
+            },
+            "use completion endpoint" => {
+                self.endpoint = Endpoint::Completion;
+                self.ter_text.clear();
+                self.ter_text.push("Using /completion (raw prompt)".to_string());
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    // Asks the server to load a model, the answer arrives as ModelLoaded
+    pub fn request_load(&mut self, model: &str) {
+        self.ter_text.clear();
+        if let Some(loading) = &self.loading_model {
+            self.ter_text.push(format!("Still loading {}, wait a moment", loading));
+            return;
+        }
+        self.ter_text.push(format!("Loading {}...", model));
+        self.loading_model = Some(model.to_string());
+
+        let server = self.server.clone();
+        let model = model.to_string();
+        self.dispatch(async move {
+            let result = server.load_model(&model).await.map_err(|e| e.to_string());
+            ClientEvent::ModelLoaded { model, result }
         });
-        // Petition
-        let res: serde_json::Value = self.client.post(format!("{}/completion", &self.url))
-            .json(&body)
-            .send()
-            .await?
-            .json()
-            .await?;
+    }
 
-        // Answer Processing
-        let mut content = res["content"]
-            .as_str()
-            .ok_or("Failed to get content")?
-            .trim()
-            .to_string();
-        
-        // Cleaning
-        if content.starts_with("Assistant:") {
-            content = content.replace("Assistant:", "").trim().to_string();
+    // Frees a model's memory, the router keeps it in the list as "unloaded"
+    pub fn request_unload(&mut self, model: &str) {
+        self.ter_text.clear();
+        self.ter_text.push(format!("Unloading {}...", model));
+
+        let server = self.server.clone();
+        let model = model.to_string();
+        self.dispatch(async move {
+            let result = server.unload_model(&model).await.map_err(|e| e.to_string());
+            ClientEvent::ModelUnloaded { model, result }
+        });
+    }
+
+    // Renders a message into the styled chat lines
+    fn push_history(&mut self, message: &Message) {
+        let (prefix, color) = match message.role {
+            Role::User => ("You: ", Color::Cyan),
+            Role::Assistant => ("AI: ", Color::Yellow),
+        };
+        for (i, piece) in message.content.split('\n').enumerate() {
+            let span = Span::styled(piece.to_string(), Style::default().fg(color));
+            if i == 0 {
+                self.history.push(Line::from(vec![Span::raw(prefix), span]));
+            } else {
+                self.history.push(Line::from(span));
+            }
         }
-        Ok(content)
     }
 
-    // This method should be called in your main loop every "frame" or "tick"
-    pub fn update_terminal_text(&mut self) {
-        while let Ok(msg) = self.rx.try_recv() {
-            self.ter_text.push(msg);
-            
-            // Keep only the last 5 lines
-            if self.ter_text.len() > 10 {
-                self.ter_text.remove(0);
+    // Appends a token to the last line of the chat, opening new lines on '\n'
+    fn push_token(&mut self, token: &str) {
+        if let Some(message) = self.messages.last_mut() {
+            let token = if message.content.is_empty() { token.trim_start() } else { token };
+            message.content.push_str(token);
+        }
+
+        for (i, piece) in token.split('\n').enumerate() {
+            if i > 0 {
+                self.history.push(Line::from(Span::styled(String::new(), Style::default().fg(Color::Yellow))));
+            }
+            if let Some(span) = self.history.last_mut().and_then(|line| line.spans.last_mut()) {
+                // Skip the leading whitespace of the answer
+                let piece = if span.content.is_empty() { piece.trim_start() } else { piece };
+                span.content.to_mut().push_str(piece);
             }
         }
     }
 
     // Parsing Commands
-    pub async fn parsing(&mut self, app: &mut App) {
+    // Nothing here waits on the server: requests are dispatched to tasks
+    // and their results come back through handle_events.
+    pub fn parsing(&mut self, app: &mut App) {
         let text: String = self.user_text.drain(..).collect();
+        if self.common_command(app, &text) {
+            return;
+        }
         match app.current_screen {
             // Parsing for the Config Page
             CurrentScreen::Config => {
+                if let Some(args) = text.strip_prefix("set ") {
+                    self.set_setting(app, args);
+                    return;
+                }
+                if let Some(url) = text.strip_prefix("connect ") {
+                    self.connect(url.trim());
+                    return;
+                }
                 match text.as_str() {
-                    "go chat" => app.to_chat(),
-                    "get health" => { 
-                        if let Ok(health) = self.get_health().await {
-                            self.ter_text.clear(); // Clear old logs
-                            self.ter_text.push(health); // Add the new one
-                        } else {
-                            self.ter_text.clear();
-                            self.ter_text.push("Error: Server unreachable".to_string());
-                        }
+                    "go chat" => {
+                        app.refresh_models();
+                        app.go_chat();
                     },
-                    "list models" => { 
-                        if let Ok(models) = self.get_models().await {
-                            self.ter_text.clear();
-                            // readable returns a String, so we push it
-                            self.ter_text.push(self.readable(&models)); 
+                    "go logs" => app.go_logs(),
+                    "start server" => self.start_server(),
+                    "disconnect" => self.disconnect(),
+                    "stop server" => {
+                        self.ter_text.clear();
+                        if self.stop_llama(false) {
+                            self.ter_text.push("Stopping Llama Server...".to_string());
                         } else {
-                            self.ter_text.clear();
-                            self.ter_text.push("Error: Could not retrieve models".to_string());
+                            self.ter_text.push("No Llama Server of ours is running".to_string());
                         }
                     },
-                    "start server" => { 
-                        let _ = self.start_llama().await; 
+                    "restart server" => {
                         self.ter_text.clear();
-                        self.ter_text.push("Llama Server Started".to_string());
-                    },
-                    "load model" => { 
-                        if let Ok(res) = self.load_model("qwen").await {
-                            self.ter_text.clear();
-                            self.ter_text.push(format!("Model Loaded: {}", res));
-                        }
-                    },
-                    "load model qwen" => { 
-                        if let Ok(res) = self.load_model("qwen").await {
-                            self.ter_text.clear();
-                            self.ter_text.push(format!("Model Loaded: {}", res));
+                        if self.stop_llama(true) {
+                            self.ter_text.push("Restarting Llama Server...".to_string());
+                        } else {
+                            self.start_server();
                         }
                     },
-                    "load model phi2" => { 
-                        if let Ok(res) = self.load_model("phi2").await {
-                            self.ter_text.clear();
-                            self.ter_text.push(format!("Model Loaded: {}", res));
-                        }
+                    "save config" => self.save_config(app),
+                    "show settings" => {
+                        self.ter_text.clear();
+                        self.ter_text.extend(self.settings.lines());
                     },
-                    "load model danube" => { 
-                        if let Ok(res) = self.load_model("danube").await {
-                            self.ter_text.clear();
-                            self.ter_text.push(format!("Model Loaded: {}", res));
-                        }
+                    "server status" => {
+                        self.ter_text.clear();
+                        self.ter_text.push(format!("Llama Server: {}", self.server_state.label()));
                     },
                     "install engine" => {
                         let tx = self.tx.clone();
@@ -254,52 +1211,66 @@ impl LlamaClient {
                             install_engine(tx).await;
                         });
                     },
-                    "install models" => {
-                        let tx = self.tx.clone();
-                        tokio::spawn(async move {
-                            install_models(tx).await;
-                        });
-                    },
+                    "install models" => self.install_catalog(app, true),
+                    // For models whose catalog entry has no sha256
+                    "install models unverified" => self.install_catalog(app, false),
                     _ => {},
                 }
             }
             // Parsing for the Chat Page
             CurrentScreen::Chat => {
+                if let Some(name) = text.strip_prefix("save chat ") {
+                    self.save_chat(name.trim());
+                    return;
+                }
+                if let Some(name) = text.strip_prefix("load chat ") {
+                    self.load_chat(app, name.trim());
+                    return;
+                }
+                if let Some(args) = text.strip_prefix("export chat ") {
+                    self.export_chat(args.trim());
+                    return;
+                }
                 match text.as_str() {
-                    "go config" => app.to_config(),
-                    "get health" => { 
-                        if let Ok(health) = self.get_health().await {
-                            self.ter_text.clear(); // Clear old logs
-                            self.ter_text.push(health); // Add the new one
-                        } else {
-                            self.ter_text.clear();
-                            self.ter_text.push("Error: Server unreachable".to_string());
-                        }
-                    },
-                    "list models" => { 
-                        if let Ok(models) = self.get_models().await {
-                            self.ter_text.clear();
-                            // readable returns a String, so we push it
-                            self.ter_text.push(self.readable(&models)); 
+                    "go config" => app.go_config(),
+                    "go logs" => app.go_logs(),
+                    "list chats" => {
+                        self.ter_text.clear();
+                        // The autosave isn't a chat anyone saved
+                        let names: Vec<String> = session::list()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .filter(|name| name != LAST_SESSION)
+                            .collect();
+                        if names.is_empty() {
+                            self.ter_text.push("No saved chats.".to_string());
                         } else {
-                            self.ter_text.clear();
-                            self.ter_text.push("Error: Could not retrieve models".to_string());
+                            self.ter_text.push(names.join("\n"));
                         }
                     },
                     _ => {
-                        // User message added
-                        self.history.push(Line::from(vec![
-                            Span::raw("You: "),
-                            Span::styled(text.clone(), Style::default().fg(Color::Cyan)),
-                        ]));
-
-                        // AI response added
-                        if let Ok(response) = self.ask(&text).await {
-                            self.history.push(Line::from(vec![
-                                Span::raw("AI: "),
-                                Span::styled(response, Style::default().fg(Color::Yellow)),
-                            ]));
+                        // One answer at a time
+                        if self.generating {
+                            self.ter_text.push("Still answering, wait or press Ctrl+C".to_string());
+                            return;
                         }
+
+                        // User message added
+                        self.push_history(&Message::new(Role::User, &text));
+
+                        // AI response streamed in
+                        self.ask(&text);
+                    }
+                }
+            }
+            // Parsing for the Log Viewer: anything that isn't a command is the search
+            CurrentScreen::Logs => {
+                match text.as_str() {
+                    "go config" => app.go_config(),
+                    "go chat" => app.go_chat(),
+                    _ => {
+                        app.log_view.search = text;
+                        app.log_view.scroll = 0;
                     }
                 }
             }
@@ -307,4 +1278,44 @@ impl LlamaClient {
         }
         
     }
-}
\ No newline at end of file
+}
+
+// SIGTERM first, so the router can stop the model processes it spawned.
+// Only a server still there after STOP_TIMEOUT gets killed outright.
+async fn terminate(mut child: Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: kill(2) has no memory effects, and the pid is our unreaped child
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+        if tokio::time::timeout(STOP_TIMEOUT, child.wait()).await.is_ok() {
+            return;
+        }
+    }
+    let _ = child.kill().await;
+}
+
+// Same idea as install_engine: both streams at once, one event per line
+async fn forward_logs(stdout: ChildStdout, stderr: ChildStderr, tx: UnboundedSender<ClientEvent>) {
+    let mut stdout_reader = BufReader::new(stdout).lines();
+    let mut stderr_reader = BufReader::new(stderr).lines();
+    let (mut stdout_open, mut stderr_open) = (true, true);
+
+    while stdout_open || stderr_open {
+        tokio::select! {
+            res = stdout_reader.next_line(), if stdout_open => {
+                match res {
+                    Ok(Some(line)) => { let _ = tx.send(ClientEvent::ServerLog(line)); }
+                    _ => stdout_open = false,
+                }
+            }
+            res = stderr_reader.next_line(), if stderr_open => {
+                match res {
+                    Ok(Some(line)) => { let _ = tx.send(ClientEvent::ServerLog(line)); }
+                    _ => stderr_open = false,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// src/logs.rs
+
+// Generic Imports
+use std::collections::VecDeque;
+
+// How many llama-server lines we keep around
+pub const LOG_LINES: usize = 2000;
+
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    // With --log-prefix llama-server starts every line with "D ", "I ", "W " or "E "
+    pub fn parse(line: &str) -> Self {
+        match line.split_whitespace().next() {
+            Some("D") => LogLevel::Debug,
+            Some("I") => LogLevel::Info,
+            Some("W") => LogLevel::Warn,
+            Some("E") => LogLevel::Error,
+            // No prefix, so we guess
+            _ => {
+                let lower = line.to_lowercase();
+                if lower.contains("error") || lower.contains("failed") {
+                    LogLevel::Error
+                } else if lower.contains("warn") {
+                    LogLevel::Warn
+                } else {
+                    LogLevel::Info
+                }
+            }
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "all",
+            LogLevel::Info => "info+",
+            LogLevel::Warn => "warn+",
+            LogLevel::Error => "error",
+        }
+    }
+
+    // Tab in the log viewer goes through them
+    pub fn next(&self) -> Self {
+        match self {
+            LogLevel::Debug => LogLevel::Info,
+            LogLevel::Info => LogLevel::Warn,
+            LogLevel::Warn => LogLevel::Error,
+            LogLevel::Error => LogLevel::Debug,
+        }
+    }
+}
+
+pub struct LogLine {
+    pub level: LogLevel,
+    pub text: String,
+}
+
+// Bounded: the oldest line goes when a new one doesn't fit
+pub struct LogBuffer {
+    pub lines: VecDeque<LogLine>,
+}
+
+impl LogBuffer {
+    pub fn new() -> Self {
+        Self { lines: VecDeque::new() }
+    }
+
+    pub fn push(&mut self, text: String) {
+        if self.lines.len() == LOG_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(LogLine { level: LogLevel::parse(&text), text });
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    // The last n lines, whatever their level
+    pub fn tail(&self, n: usize) -> impl Iterator<Item = &LogLine> {
+        self.lines.iter().skip(self.lines.len().saturating_sub(n))
+    }
+}
+
+// What the log viewer shows
+pub struct LogView {
+    pub level: LogLevel,
+    pub search: String,
+    // Lines up from the bottom, 0 follows new lines
+    pub scroll: usize,
+    // Where the first line reaches the top, set every time the logs are drawn
+    pub max_scroll: usize,
+}
+
+impl LogView {
+    pub fn new() -> Self {
+        Self { level: LogLevel::Debug, search: String::new(), scroll: 0, max_scroll: 0 }
+    }
+
+    pub fn filter<'a>(&self, buffer: &'a LogBuffer) -> Vec<&'a LogLine> {
+        let search = self.search.to_lowercase();
+        buffer.lines
+            .iter()
+            .filter(|line| line.level >= self.level)
+            .filter(|line| search.is_empty() || line.text.to_lowercase().contains(&search))
+            .collect()
+    }
+
+    // Scrolling can't go past the first line
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_add(lines).min(self.max_scroll);
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+}
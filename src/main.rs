@@ -1,45 +1,121 @@
 // src/main.rs
 
 // Generic Imports
-use ratatui::{
-    text::{Line, Span},          
-    style::{Color, Style, Stylize}, 
-    DefaultTerminal,};
-use crossterm::event::{self, Event, KeyCode};
+use ratatui::DefaultTerminal;
+use crossterm::event::{poll, read, Event, KeyCode, KeyModifiers};
 use std::time::Duration;
 // My Imports
 mod app;
+mod backend;
+mod catalog;
+mod chat;
+mod cli;
+mod config;
 mod download;
+mod event;
+mod gguf;
+mod headless;
 mod llama;
+mod logs;
+mod memory;
+mod openai;
+mod session;
+mod settings;
+mod stats;
 mod ui;
 
 use app::{App, CurrentScreen};
+use clap::Parser;
+use cli::{Cli, Command};
+use config::Config;
 use llama::LlamaClient;
-use ui::{show_chat, show_config, show_welcome};
+use ui::{show_chat, show_config, show_logs, show_welcome};
 
 // ENTRANCE
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
-    // Initialize 
-    let mut terminal = ratatui::init();
+    // Before the TUI takes the terminal, so --help and errors print normally
+    let cli = Cli::parse();
     let mut app = App::new();
     let mut client = LlamaClient::new();
 
+    // Headless: one question, one answer, no TUI
+    if let Some(Command::Ask { prompt }) = &cli.command {
+        setup(&cli, &mut app, &mut client, false);
+        let result = match headless::read_prompt(prompt) {
+            Ok(prompt) => headless::ask(&mut client, &mut app, &prompt).await,
+            Err(e) => Err(e),
+        };
+        client.shutdown().await;
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Initialize 
+    let mut terminal = ratatui::init();
+    setup(&cli, &mut app, &mut client, true);
+    if let Some(screen) = cli.screen() {
+        app.current_screen = screen;
+    }
+    if cli.start_server {
+        client.load_when_ready = true;
+        client.start_server();
+    } else if let Some(remote) = client.settings.remote.clone() {
+        client.connect(&remote);
+    }
+    app.select_model(&client.actual_model);
+
     // Run Main Loop
-    let result = run(&mut terminal, &mut app, &mut client).await;
+    let result = run(&mut terminal, &mut app, &mut client);
     
-    // Clean
-    let _ = std::process::Command::new("pkill")
-        .args(["-f", "llama-server"])
-        .spawn();
+    // Clean (only the llama-server we started)
+    client.save_last();
+    client.shutdown().await;
     ratatui::restore();
     result
 }
 
-async fn run(terminal: &mut DefaultTerminal, app: &mut App, client: &mut LlamaClient) -> std::io::Result<()> {
+// Config file, catalog, last chat and then the command-line flags on top
+fn setup(cli: &Cli, app: &mut App, client: &mut LlamaClient, restore_last: bool) {
+    client.config_path = cli.config.clone().or_else(config::default_path);
+    let config = match client.config_path.as_deref().map(Config::load) {
+        Some(Ok(config)) => config,
+        Some(Err(e)) => {
+            client.ter_text.push(format!("Error: Could not read the config, using defaults ({})", e));
+            Config::default()
+        }
+        None => Config::default(),
+    };
+    client.apply_config(app, config);
+    if let Err(e) = app.load_catalog() {
+        client.ter_text.push(format!("Error: Could not read the model catalog ({})", e));
+    }
+    if restore_last && client.config.ui.restore_last {
+        client.restore_last();
+    }
+
+    // Command-line flags win over the config and the last chat
+    if let Some(model) = &cli.model {
+        client.actual_model = model.clone();
+    }
+    if let Some(url) = &cli.url {
+        match settings::parse_url(url) {
+            Ok(url) => {
+                client.settings.remote = Some(url);
+                client.use_url(client.settings.server_url());
+            }
+            Err(e) => client.ter_text.push(format!("Error: --url {}", e)),
+        }
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App, client: &mut LlamaClient) -> std::io::Result<()> {
     loop {
-        // For Install Scripts
-        client.update_terminal_text();
+        // Results from the Background Tasks (installs, requests, answers)
+        client.handle_events(app);
         // For Refreshing the Screen
         terminal.draw(|f| match app.current_screen {
                 // The Welcome Screen
@@ -48,19 +124,21 @@ async fn run(terminal: &mut DefaultTerminal, app: &mut App, client: &mut LlamaCl
                 CurrentScreen::Config => show_config(f, app, client),
                 // The Chat Terminal
                 CurrentScreen::Chat => show_chat(f, app, client),
+                // The llama-server Logs
+                CurrentScreen::Logs => show_logs(f, app, client),
         })?;
 
         // Run the Loop every 30ms
-        if event::poll(Duration::from_millis(30))? {
+        if poll(Duration::from_millis(30))? {
             // We Check for Keyboard Actions
-            if let Event::Key(key) = event::read()? {
+            if let Event::Key(key) = read()? {
                 match app.current_screen {
                     // Welcome Actions
                     // This Screen is just for show, it's pretty. 
                     // We can either exit or go to Config
                     CurrentScreen::Welcome => {
                         match key.code {
-                            KeyCode::Enter => app.to_config(),
+                            KeyCode::Enter => app.go_config(),
                             KeyCode::Esc => break Ok(()),
                             _ => {}
                         }
@@ -71,7 +149,7 @@ async fn run(terminal: &mut DefaultTerminal, app: &mut App, client: &mut LlamaCl
                     CurrentScreen::Config => {
                         match key.code {
                             KeyCode::Enter => {
-                                client.parsing(app).await;
+                                client.parsing(app);
                             },
                             KeyCode::Esc => break Ok(()),
                             // Writing
@@ -88,29 +166,61 @@ async fn run(terminal: &mut DefaultTerminal, app: &mut App, client: &mut LlamaCl
                         match key.code {
                             // Asking
                             KeyCode::Enter => {
-                                client.parsing(app).await;
+                                client.parsing(app);
                             }
                             // Exiting
                             KeyCode::Esc => break Ok(()),
+                            // Stopping the Answer
+                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => client.cancel(app),
                             // Writing
                             KeyCode::Char(c) => client.user_text.push(c),
                             // Deleting
                             KeyCode::Backspace => { client.user_text.pop(); },
                             
                             // Selection Commands
-                            KeyCode::Up => {
-                                if app.selected_model_index > 0 {
-                                    app.selected_model_index -= 1;
-                                }
+                            KeyCode::Up if app.selected_model_index > 0 => {
+                                app.selected_model_index -= 1;
+                            }
+                            KeyCode::Down if app.selected_model_index + 1 < app.models.len() => {
+                                app.selected_model_index += 1;
                             }
-                            KeyCode::Down => {
-                                if app.selected_model_index < app.models.len() - 1 {
-                                    app.selected_model_index += 1;
+                            // Loading the Highlighted Model
+                            KeyCode::Tab => {
+                                if let Some(model) = app.selected_model().cloned() {
+                                    // Our own server only has models/, anyone else's knows what it serves
+                                    let missing = app.catalog.get(&model).is_some() && !app.installed.contains(&model);
+                                    if missing && client.process.is_some() {
+                                        client.ter_text.push(format!("{} is not downloaded yet, run \"install models\"", model));
+                                    } else {
+                                        client.request_load(&model);
+                                    }
                                 }
                             }
                             _ => {}
                         }
                     }
+                    // Log Viewer Actions
+                    // Scrolling, filtering by level and searching
+                    CurrentScreen::Logs => {
+                        match key.code {
+                            // Searching (or leaving with "go config")
+                            KeyCode::Enter => {
+                                client.parsing(app);
+                            }
+                            KeyCode::Esc => break Ok(()),
+                            KeyCode::Char(c) => client.user_text.push(c),
+                            KeyCode::Backspace => { client.user_text.pop(); },
+                            // Level Filter
+                            KeyCode::Tab => app.log_view.level = app.log_view.level.next(),
+                            // Scrolling
+                            KeyCode::Up => app.log_view.scroll_up(1),
+                            KeyCode::Down => app.log_view.scroll_down(1),
+                            KeyCode::PageUp => app.log_view.scroll_up(20),
+                            KeyCode::PageDown => app.log_view.scroll_down(20),
+                            KeyCode::End => app.log_view.scroll = 0,
+                            _ => {}
+                        }
+                    }
                 }
             }
         }
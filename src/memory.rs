@@ -0,0 +1,80 @@
+// src/memory.rs
+
+// Generic Imports
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// How much RAM our llama-server holds. In router mode every loaded model
+// runs in a child process, so the children tell us the cost per model.
+// Read from /proc, so it stays empty outside Linux.
+#[derive(Clone, Default)]
+pub struct MemoryReport {
+    // The router itself
+    pub server: Option<u64>,
+    // Model name -> bytes, for every child serving a model
+    pub models: HashMap<String, u64>,
+}
+
+impl MemoryReport {
+    // Router plus children
+    pub fn total(&self) -> Option<u64> {
+        self.server.map(|server| server + self.models.values().sum::<u64>())
+    }
+}
+
+pub fn report(pid: u32) -> MemoryReport {
+    let mut models = HashMap::new();
+    for child in children(pid) {
+        if let (Some(model), Some(bytes)) = (served_model(child), resident(child)) {
+            models.insert(model, bytes);
+        }
+    }
+    MemoryReport { server: resident(pid), models }
+}
+
+// VmRSS from /proc/<pid>/status, in bytes
+fn resident(pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+// Every process whose parent is pid
+fn children(pid: u32) -> Vec<u32> {
+    let Ok(entries) = fs::read_dir("/proc") else { return Vec::new() };
+    entries
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|&candidate| parent(candidate) == Some(pid))
+        .collect()
+}
+
+// /proc/<pid>/stat is "pid (name) state ppid ...", and the name may hold spaces
+fn parent(pid: u32) -> Option<u32> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let after_name = &stat[stat.rfind(')')? + 1..];
+    after_name.split_whitespace().nth(1)?.parse().ok()
+}
+
+// The router starts its children with "-m <file>.gguf", named like the router names them
+fn served_model(pid: u32) -> Option<String> {
+    let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let args: Vec<String> = cmdline
+        .split(|&b| b == 0)
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    let position = args.iter().position(|arg| arg == "-m" || arg == "--model")?;
+    let file = args.get(position + 1)?;
+    Some(Path::new(file).file_stem()?.to_string_lossy().into_owned())
+}
+
+// "812 MB", "1.4 GB"
+pub fn format_bytes(bytes: u64) -> String {
+    let mb = bytes as f64 / (1024.0 * 1024.0);
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{:.0} MB", mb)
+    }
+}
@@ -0,0 +1,92 @@
+// src/openai.rs
+
+// Generic Imports
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::sync::mpsc::UnboundedSender;
+
+// My Imports
+use crate::backend::{authorized, stream_tokens, Backend, BoxError, Connection, RemoteModel};
+use crate::chat::{Message, Sampling, STOP};
+use crate::event::ClientEvent;
+use crate::stats::ServerStats;
+
+// Any server speaking the OpenAI API under /v1 (Ollama, vLLM, a mock...).
+// Models load on their first request, so there is nothing to load or unload.
+pub struct OpenAiServer {
+    pub connection: Connection,
+}
+
+#[async_trait]
+impl Backend for OpenAiServer {
+    fn url(&self) -> &str {
+        &self.connection.url
+    }
+
+    // There is no standard /health, answering /v1/models is as healthy as it gets
+    async fn health(&self) -> Result<String, BoxError> {
+        let models = self.list_models().await?;
+        Ok(format!("ok ({} models)", models.len()))
+    }
+
+    async fn is_ready(&self) -> Result<bool, BoxError> {
+        let res = authorized(self.connection.get("v1/models").send().await?)?;
+        Ok(res.status().is_success())
+    }
+
+    async fn list_models(&self) -> Result<Vec<RemoteModel>, BoxError> {
+        let res: serde_json::Value = authorized(self.connection.get("v1/models").send().await?)?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let models = res["data"]
+            .as_array()
+            .map(|data| data.iter()
+                .filter_map(|m| m["id"].as_str())
+                .map(|id| RemoteModel { id: id.to_string(), status: None })
+                .collect())
+            .unwrap_or_default();
+        Ok(models)
+    }
+
+    // Just checks the server has it
+    async fn load_model(&self, model: &str) -> Result<String, BoxError> {
+        if self.list_models().await?.iter().any(|m| m.id == model) {
+            Ok("loads on first request".to_string())
+        } else {
+            Err(format!("{} is not served at {}", model, self.connection.url).into())
+        }
+    }
+
+    async fn unload_model(&self, _model: &str) -> Result<String, BoxError> {
+        Err("OpenAI-compatible servers don't unload models on request".into())
+    }
+
+    async fn complete(&self, model: &str, prompt: &str, sampling: &Sampling, tx: &UnboundedSender<ClientEvent>) -> Result<(), BoxError> {
+        let body = json!({
+            "model": model,
+            "prompt": prompt,
+            "max_tokens": sampling.n_predict,
+            "temperature": sampling.temperature,
+            "stop": STOP,
+            "stream": true
+        });
+        stream_tokens(self.connection.post("v1/completions"), body, tx).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[Message], sampling: &Sampling, tx: &UnboundedSender<ClientEvent>) -> Result<(), BoxError> {
+        let body = json!({
+            "model": model,
+            "messages": messages,
+            "max_tokens": sampling.n_predict,
+            "temperature": sampling.temperature,
+            "stream": true
+        });
+        stream_tokens(self.connection.post("v1/chat/completions"), body, tx).await
+    }
+
+    async fn stats(&self, _model: &str) -> Result<ServerStats, BoxError> {
+        Err("OpenAI-compatible servers don't share stats".into())
+    }
+}
@@ -0,0 +1,191 @@
+// src/session.rs
+
+// Generic Imports
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// My Imports
+use crate::chat::{Message, Role, Sampling};
+
+// Name of the session written on exit and restored on launch
+pub const LAST_SESSION: &str = "last";
+
+// A saved conversation, one JSON file per session
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    pub name: String,
+    pub model: String,
+    pub created: DateTime<Local>,
+    pub updated: DateTime<Local>,
+    pub sampling: Sampling,
+    pub messages: Vec<Message>,
+}
+
+// Formats for "export chat"
+#[derive(Clone, Copy)]
+pub enum ExportFormat {
+    Markdown,
+    Text,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "md" | "markdown" => Some(Self::Markdown),
+            "txt" | "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    // Guessed from the file extension, Markdown if there is none
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|ext| Self::parse(&ext.to_string_lossy()))
+            .unwrap_or(Self::Markdown)
+    }
+}
+
+// Where chats live (~/.local/share/chalante/chats on Linux)
+pub fn chats_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("chalante")
+        .join("chats")
+}
+
+// Names end up as file names, so keep them simple
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+impl Session {
+    pub fn save(&self) -> std::io::Result<PathBuf> {
+        let dir = chats_dir();
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.json", self.name));
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    pub fn load(name: &str) -> std::io::Result<Self> {
+        let raw = fs::read_to_string(chats_dir().join(format!("{}.json", name)))?;
+        Ok(serde_json::from_str(&raw)?)
+    }
+}
+
+// Every saved chat, sorted by name
+pub fn list() -> std::io::Result<Vec<String>> {
+    let mut names: Vec<String> = fs::read_dir(chats_dir())?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+// Transcripts
+impl Session {
+    pub fn export(&self, path: &Path, format: ExportFormat) -> std::io::Result<()> {
+        let content = match format {
+            ExportFormat::Markdown => self.to_markdown(),
+            ExportFormat::Text => self.to_text(),
+            ExportFormat::Json => serde_json::to_string_pretty(self)?,
+        };
+        fs::write(path, content)
+    }
+
+    // Messages are written untouched, so code fences survive
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# Chalante chat: {}\n\n", self.name);
+        out.push_str(&format!("- **Model:** {}\n", self.model));
+        out.push_str(&format!("- **Max tokens:** {}\n", self.sampling.n_predict));
+        out.push_str(&format!("- **Temperature:** {}\n", self.sampling.temperature));
+        out.push_str(&format!("- **Started:** {}\n", self.created.format("%Y-%m-%d %H:%M")));
+        out.push_str(&format!("- **Exported:** {}\n", self.updated.format("%Y-%m-%d %H:%M")));
+
+        for message in &self.messages {
+            let heading = match message.role {
+                Role::User => "You",
+                Role::Assistant => "AI",
+            };
+            out.push_str(&format!("\n## {}\n\n{}\n", heading, message.content.trim_end()));
+        }
+        out
+    }
+
+    fn to_text(&self) -> String {
+        let mut out = format!("Chalante chat: {}\n", self.name);
+        out.push_str(&format!("Model: {}\n", self.model));
+        out.push_str(&format!("Max tokens: {}, Temperature: {}\n", self.sampling.n_predict, self.sampling.temperature));
+        out.push_str(&format!("Started: {}\n", self.created.format("%Y-%m-%d %H:%M")));
+        out.push_str(&format!("Exported: {}\n", self.updated.format("%Y-%m-%d %H:%M")));
+
+        for message in &self.messages {
+            let heading = match message.role {
+                Role::User => "You:",
+                Role::Assistant => "AI:",
+            };
+            out.push_str(&format!("\n{}\n{}\n", heading, message.content.trim_end()));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn session() -> Session {
+        Session {
+            name: "rust".to_string(),
+            model: "qwen".to_string(),
+            created: Local.with_ymd_and_hms(2026, 10, 18, 9, 30, 0).unwrap(),
+            updated: Local.with_ymd_and_hms(2026, 10, 18, 10, 5, 0).unwrap(),
+            sampling: Sampling { n_predict: 256, temperature: 0.7 },
+            messages: vec![
+                Message::new(Role::User, "How do I print in Rust?"),
+                Message::new(Role::Assistant, "Like this:\n\n```rust\nprintln!(\"hi\");\n```\n\n"),
+            ],
+        }
+    }
+
+    #[test]
+    fn markdown_has_a_header_and_one_section_per_message() {
+        let expected = "\
+# Chalante chat: rust
+
+- **Model:** qwen
+- **Max tokens:** 256
+- **Temperature:** 0.7
+- **Started:** 2026-10-18 09:30
+- **Exported:** 2026-10-18 10:05
+
+## You
+
+How do I print in Rust?
+
+## AI
+
+Like this:
+
+```rust
+println!(\"hi\");
+```
+";
+        assert_eq!(session().to_markdown(), expected);
+    }
+
+    #[test]
+    fn markdown_of_an_empty_chat_is_just_the_header() {
+        let mut session = session();
+        session.messages.clear();
+        assert!(session.to_markdown().ends_with("- **Exported:** 2026-10-18 10:05\n"));
+    }
+}
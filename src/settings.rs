@@ -0,0 +1,163 @@
+// src/settings.rs
+
+// Generic Imports
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// My Imports
+use crate::app::MODELS_DIR;
+use crate::backend::BackendKind;
+
+// Wins over api_key in the config, so the key can stay out of the file
+pub const API_KEY_ENV: &str = "CHALANTE_API_KEY";
+// How our llama-server gets the key: on the command line it would show in ps and /proc
+pub const SERVER_API_KEY_ENV: &str = "LLAMA_API_KEY";
+
+// How llama-server gets launched, applied on the next "start server"
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    // What kind of server answers, only llama-server can be launched
+    pub backend: BackendKind,
+    pub host: String,
+    pub port: u16,
+    pub binary: PathBuf,
+    pub models_dir: PathBuf,
+    // None leaves llama-server's own default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ctx_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel: Option<u32>,
+    // A llama-server somebody else runs, we only connect to it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    // Bearer token for a server started with --api-key (ours gets it too)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    // Anything else, passed as-is (split on spaces)
+    #[serde(rename = "args")]
+    pub extra_args: String,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            backend: BackendKind::Llama,
+            host: "127.0.0.1".to_string(),
+            port: 11343,
+            binary: PathBuf::from("llama.cpp/build/bin/llama-server"),
+            models_dir: PathBuf::from(MODELS_DIR),
+            ctx_size: None,
+            threads: None,
+            batch_size: None,
+            parallel: None,
+            remote: None,
+            api_key: None,
+            extra_args: String::new(),
+        }
+    }
+}
+
+// "http://host:port", without the trailing slash
+pub fn parse_url(url: &str) -> Result<String, String> {
+    let parsed = Url::parse(url).map_err(|e| format!("'{}' is not a URL ({})", url, e))?;
+    if parsed.host_str().is_none() {
+        return Err(format!("'{}' has no host", url));
+    }
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+// "auto" (or "default") clears an optional number
+fn optional_number(value: &str) -> Result<Option<u32>, String> {
+    match value {
+        "auto" | "default" => Ok(None),
+        _ => value.parse().map(Some).map_err(|_| format!("'{}' is not a number", value)),
+    }
+}
+
+impl ServerSettings {
+    // Where the server we launch will answer
+    pub fn url(&self) -> String {
+        format!("http://{}:{}", self.host, self.port)
+    }
+
+    // Where requests go: the remote server if there is one, ours otherwise
+    pub fn server_url(&self) -> String {
+        self.remote.clone().unwrap_or_else(|| self.url())
+    }
+
+    // The key actually sent: the environment first, then the config
+    pub fn api_key(&self) -> Option<String> {
+        std::env::var(API_KEY_ENV).ok().filter(|key| !key.is_empty()).or_else(|| self.api_key.clone())
+    }
+
+    // Command-line arguments for llama-server
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--models-dir".to_string(), self.models_dir.display().to_string(),
+            "--host".to_string(), self.host.clone(),
+            "--port".to_string(), self.port.to_string(),
+            "--log-prefix".to_string(),
+            // For the Stats panel
+            "--metrics".to_string(),
+        ];
+        let optional = [
+            ("--ctx-size", self.ctx_size),
+            ("--threads", self.threads),
+            ("--batch-size", self.batch_size),
+            ("--parallel", self.parallel),
+        ];
+        for (flag, value) in optional {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push(value.to_string());
+            }
+        }
+        args.extend(self.extra_args.split_whitespace().map(String::from));
+        args
+    }
+
+    // "set <key> <value>" from the Config screen
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "backend" => self.backend = BackendKind::parse(value).ok_or(format!("'{}' is not a backend (llama or openai)", value))?,
+            "host" => self.host = value.to_string(),
+            "port" => self.port = value.parse().map_err(|_| format!("'{}' is not a port", value))?,
+            "binary" => self.binary = PathBuf::from(value),
+            "models_dir" => self.models_dir = PathBuf::from(value),
+            "ctx_size" => self.ctx_size = optional_number(value)?,
+            "threads" => self.threads = optional_number(value)?,
+            "batch_size" => self.batch_size = optional_number(value)?,
+            "parallel" => self.parallel = optional_number(value)?,
+            "args" => self.extra_args = value.to_string(),
+            "api_key" => self.api_key = Some(value.to_string()).filter(|key| key != "none"),
+            _ => return Err(format!("Unknown setting '{}'", key)),
+        }
+        Ok(())
+    }
+
+    // One line per setting, for the Config screen
+    pub fn lines(&self) -> Vec<String> {
+        let auto = |value: Option<u32>| value.map(|v| v.to_string()).unwrap_or_else(|| "auto".to_string());
+        vec![
+            format!("backend     {}", self.backend.label()),
+            format!("host        {}", self.host),
+            format!("port        {}", self.port),
+            format!("binary      {}", self.binary.display()),
+            format!("models_dir  {}", self.models_dir.display()),
+            format!("ctx_size    {}", auto(self.ctx_size)),
+            format!("threads     {}", auto(self.threads)),
+            format!("batch_size  {}", auto(self.batch_size)),
+            format!("parallel    {}", auto(self.parallel)),
+            format!("args        {}", self.extra_args),
+            format!("remote      {}", self.remote.as_deref().unwrap_or("none")),
+            // Never on screen
+            format!("api_key     {}", if self.api_key().is_some() { "set" } else { "none" }),
+        ]
+    }
+}
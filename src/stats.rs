@@ -0,0 +1,133 @@
+// src/stats.rs
+
+// Generic Imports
+use std::collections::HashMap;
+
+// Names llama-server uses in /metrics (started with --metrics)
+const PROMPT_TOKENS: &str = "llamacpp:prompt_tokens_total";
+const PREDICTED_TOKENS: &str = "llamacpp:tokens_predicted_total";
+const PROMPT_SPEED: &str = "llamacpp:prompt_tokens_seconds";
+const PREDICTED_SPEED: &str = "llamacpp:predicted_tokens_seconds";
+const KV_USAGE: &str = "llamacpp:kv_cache_usage_ratio";
+const KV_TOKENS: &str = "llamacpp:kv_cache_tokens";
+
+// One snapshot of /props, /slots and /metrics.
+// Every field is optional: older servers, or one without --metrics, leave gaps.
+#[derive(Clone, Default)]
+pub struct ServerStats {
+    pub context: Option<u64>,
+    pub slots: Option<usize>,
+    pub busy_slots: Option<usize>,
+    pub prompt_tokens: Option<f64>,
+    pub predicted_tokens: Option<f64>,
+    // Average tokens per second
+    pub prompt_speed: Option<f64>,
+    pub predicted_speed: Option<f64>,
+    // 0.0 to 1.0
+    pub kv_usage: Option<f64>,
+    pub kv_tokens: Option<f64>,
+}
+
+impl ServerStats {
+    // /props: the context every slot gets
+    pub fn read_props(&mut self, props: &serde_json::Value) {
+        self.context = props["default_generation_settings"]["n_ctx"]
+            .as_u64()
+            .or_else(|| props["n_ctx"].as_u64());
+    }
+
+    // /slots: one entry per slot, busy ones are processing a request
+    pub fn read_slots(&mut self, slots: &serde_json::Value) {
+        let Some(slots) = slots.as_array() else { return };
+        self.slots = Some(slots.len());
+        self.busy_slots = Some(slots.iter().filter(|slot| slot["is_processing"].as_bool().unwrap_or(false)).count());
+        if self.context.is_none() {
+            self.context = slots.first().and_then(|slot| slot["n_ctx"].as_u64());
+        }
+    }
+
+    // /metrics: Prometheus text
+    pub fn read_metrics(&mut self, text: &str) {
+        let metrics = parse_metrics(text);
+        self.prompt_tokens = metrics.get(PROMPT_TOKENS).copied();
+        self.predicted_tokens = metrics.get(PREDICTED_TOKENS).copied();
+        self.prompt_speed = metrics.get(PROMPT_SPEED).copied();
+        self.predicted_speed = metrics.get(PREDICTED_SPEED).copied();
+        self.kv_tokens = metrics.get(KV_TOKENS).copied();
+        // Newer servers dropped the ratio, the token count still tells
+        self.kv_usage = metrics.get(KV_USAGE).copied().or_else(|| {
+            let total = self.context? as f64 * self.slots.unwrap_or(1) as f64;
+            Some(self.kv_tokens? / total).filter(|_| total > 0.0)
+        });
+    }
+
+    // What the Stats panel shows, "-" for anything the server didn't say
+    pub fn lines(&self) -> Vec<String> {
+        let number = |value: Option<f64>| value.map(|v| format!("{:.0}", v)).unwrap_or_else(|| "-".to_string());
+        let speed = |value: Option<f64>| value.map(|v| format!(" ({:.1} tok/s)", v)).unwrap_or_default();
+
+        let slots = match (self.busy_slots, self.slots) {
+            (Some(busy), Some(total)) => format!("{}/{} busy", busy, total),
+            _ => "-".to_string(),
+        };
+        let kv = match self.kv_usage {
+            Some(usage) => format!("{:.0}%", usage * 100.0),
+            None => "-".to_string(),
+        };
+        let kv_tokens = self.kv_tokens.map(|t| format!(" ({:.0} tokens)", t)).unwrap_or_default();
+
+        vec![
+            format!("Context:   {}", self.context.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string())),
+            format!("Slots:     {}", slots),
+            format!("Prompt:    {} tokens{}", number(self.prompt_tokens), speed(self.prompt_speed)),
+            format!("Generated: {} tokens{}", number(self.predicted_tokens), speed(self.predicted_speed)),
+            format!("KV cache:  {}{}", kv, kv_tokens),
+        ]
+    }
+}
+
+// "name{labels} value" lines, comments skipped, labels ignored
+fn parse_metrics(text: &str) -> HashMap<String, f64> {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, value) = line.rsplit_once(' ')?;
+            let name = name.split('{').next()?.trim();
+            Some((name.to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: &str = "\
+# HELP llamacpp:prompt_tokens_total Number of prompt tokens processed.
+# TYPE llamacpp:prompt_tokens_total counter
+llamacpp:prompt_tokens_total 1024
+llamacpp:predicted_tokens_seconds{model=\"qwen\"} 38.5
+llamacpp:kv_cache_tokens 256
+not a number here
+";
+
+    #[test]
+    fn metrics_skip_comments_and_labels() {
+        let metrics = parse_metrics(METRICS);
+        assert_eq!(metrics.get(PROMPT_TOKENS), Some(&1024.0));
+        assert_eq!(metrics.get(PREDICTED_SPEED), Some(&38.5));
+        assert_eq!(metrics.get(KV_TOKENS), Some(&256.0));
+        // The comments and the broken line
+        assert_eq!(metrics.len(), 3);
+    }
+
+    #[test]
+    fn kv_usage_falls_back_to_the_token_count() {
+        let mut stats = ServerStats { context: Some(512), slots: Some(2), ..Default::default() };
+        stats.read_metrics(METRICS);
+        assert_eq!(stats.kv_usage, Some(0.25));
+
+        stats.read_metrics("llamacpp:kv_cache_usage_ratio 0.5\n");
+        assert_eq!(stats.kv_usage, Some(0.5));
+    }
+}
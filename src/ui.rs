@@ -4,7 +4,7 @@
 // Generic Imports
 use ratatui::{
     layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style, Stylize},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
@@ -12,7 +12,11 @@ use ratatui::{
 
 // My Imports
 use crate::app::App; 
-use crate::llama::LlamaClient; 
+use crate::download::DownloadState;
+use crate::gguf::{format_parameters, GgufInfo, ModelDetails};
+use crate::llama::{LlamaClient, ServerState};
+use crate::logs::LogLevel; 
+use crate::memory::format_bytes;
 // WELCOME SCREEN
 pub fn show_welcome(f: &mut Frame) {
 
@@ -43,30 +47,61 @@ pub fn show_config(f: &mut Frame, app: &App, client: &LlamaClient) {
 Follow this steps if it's your first time:
 
 Install llama.cpp           ->  "install engine"
-Install Initial models      ->  "install models"
+Install catalog models      ->  "install models"
 Start llama-server          ->  "start server"
 Load the Default Model      ->  "load model"
 
-To load a specific model (quen, phi2 or danube):
+To load any model from the models/ folder:
 -> "load model <model>"
+To free its memory again:
+-> "unload model <model>"
+
+How to talk to the model (here and in the Chat Area):
+Chat template (default)     ->  "use chat endpoint"
+Raw "User:" prompt          ->  "use completion endpoint"
+
+Managing our llama-server:
+Stop / Restart it           ->  "stop server" / "restart server"
+Is it running?              ->  "server status"
+Attach to a running server  ->  "connect <url>"
+Launch our own again        ->  "disconnect"
+Change a launch setting     ->  "set <key> <value>"
+Show the launch settings    ->  "show settings"
+Keep them for next time     ->  "save config"
 
 For Diagnostics you (here and in the Chat Area):
-Get list of Cached Models   ->  "list models"
+List (and rescan) Models    ->  "list models"
+Models in catalog.toml      ->  "list catalog"
 Get Server Status           ->  "get health"
 
+Saving conversations (Chat Area):
+Save the current chat       ->  "save chat <name>"
+Load a saved chat           ->  "load chat <name>"
+List saved chats            ->  "list chats"
+Export a transcript         ->  "export chat <path> [md|txt|json]"
+
 To go from place to place:
 Go to chat Area (Config)    ->  "go chat"
-Go to Config Page (Chat)    -> "go config""#;
+Go to Config Page (Chat)    ->  "go config"
+llama-server Logs           ->  "go logs""#;
 
     let screen = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
         .split(f.area());
 
-    // Split the Interactive
+    // Split the Instructions (launch settings below)
+    let settings_lines = client.settings.lines();
+    let instructions_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(settings_lines.len() as u16 + 2)])
+        .split(screen[0]);
+
+    // Split the Interactive (one gauge per download at the bottom)
+    let downloads_height = 3 * app.download_progress.len() as u16;
     let interactive_area = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .constraints([Constraint::Percentage(30), Constraint::Min(5), Constraint::Length(downloads_height)])
         .split(screen[1]);
 
     // 2. Build the Paragraph
@@ -94,20 +129,38 @@ Go to Config Page (Chat)    -> "go config""#;
         .alignment(ratatui::layout::Alignment::Left)
         .block(Block::default()
         .borders(Borders::ALL)
-        .title(" Output "))
+        .title(" Output ")
+        .title_bottom(server_title(client)))
         .wrap(Wrap { trim: false });
 
-    
-    /* Future Feature for Installing LLama.cpp
-    let progress_bar = Gauge::default()
-        .block(Block::default().title("Downloading Llama.cpp").borders(Borders::ALL))
-        .gauge_style(Style::default().fg(Color::Magenta))
-        .percent(app.download_progress);
-    f.render_widget(progress_bar, screen[3]);
-    */
-    f.render_widget(config_panel, screen[0]);
+    let settings = Paragraph::new(settings_lines.join("\n"))
+        .block(Block::default()
+        .borders(Borders::ALL)
+        .title(" Server Settings ")
+        .title_bottom(" applied on next start "));
+
+    f.render_widget(config_panel, instructions_area[0]);
+    f.render_widget(settings, instructions_area[1]);
     f.render_widget(text, interactive_area[0]);
     f.render_widget(output, interactive_area[1]);
+
+    // Model Downloads
+    let gauge_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(app.download_progress.iter().map(|_| Constraint::Length(3)))
+        .split(interactive_area[2]);
+    for (progress, area) in app.download_progress.iter().zip(gauge_areas.iter()) {
+        let color = match progress.state {
+            DownloadState::Failed(_) => Color::Red,
+            DownloadState::Done | DownloadState::Skipped => Color::Green,
+            DownloadState::Downloading | DownloadState::Verifying => Color::Magenta,
+        };
+        let progress_bar = Gauge::default()
+            .block(Block::default().title(format!(" {} ", progress.label())).borders(Borders::ALL))
+            .gauge_style(Style::default().fg(color))
+            .percent(progress.percent());
+        f.render_widget(progress_bar, *area);
+    }
     
 
 }
@@ -123,30 +176,97 @@ pub fn show_chat(f: &mut Frame, app: &App, client: &LlamaClient) {
     // Split the Info Area
     let info_area = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(30), Constraint::Percentage(40)])
         .split(screen[0]);
 
     // THE LIST [0][0]
     let items: Vec<ListItem> = app.models
         .iter()
-        .map(|m| ListItem::new(m.as_str()))
+        .map(|m| {
+            // What we asked for wins over the last /models answer
+            let status = if client.loading_model.as_ref() == Some(m) {
+                Some("loading")
+            } else {
+                app.model_status.get(m).map(String::as_str)
+            };
+            let not_installed = !app.installed.contains(m) && app.catalog.get(m).is_some();
+
+            let mut label = m.clone();
+            if *m == client.actual_model {
+                label.push_str(" (in use)");
+            }
+            if let Some(status) = status {
+                label.push_str(&format!(" [{}]", status));
+            }
+            if let Some(bytes) = client.memory.models.get(m) {
+                label.push_str(&format!(" {}", format_bytes(*bytes)));
+            }
+            if not_installed {
+                label.push_str(" (not installed)");
+            }
+
+            let color = match status {
+                Some("loaded") => Color::Green,
+                Some("loading") => Color::Yellow,
+                Some("unloaded") | None if not_installed => Color::DarkGray,
+                Some("unloaded") => Color::Reset,
+                // Statuses we don't know are usually failures
+                Some(_) => Color::Red,
+                None if *m == client.actual_model => Color::Green,
+                None => Color::Reset,
+            };
+            ListItem::new(label).style(Style::default().fg(color))
+        })
         .collect();
     
     let list = List::new(items)
         .block(Block::default()
         .borders(Borders::ALL)
-        .title(" Available Models "))
+        .title(" Available Models ")
+        .title_bottom(" [TAB] load "))
         .highlight_style(Style::default().bg(Color::Green).fg(Color::Black))
         .highlight_symbol(">> ");
     
     let mut state = ListState::default();
     state.select(Some(app.selected_model_index));
 
-    // THE STATS [0][1]
-    let stats = Paragraph::new(client.ter_text.join("\n"))
+    // THE MODEL DETAILS [0][1]
+    let details_text = match app.selected_model().and_then(|m| app.model_details.get(m)) {
+        Some(ModelDetails::Ready(info)) => model_details(info),
+        Some(ModelDetails::Loading) => vec![Line::from("Reading GGUF header...")],
+        Some(ModelDetails::Failed(e)) => vec![Line::from(format!("Could not read GGUF: {}", e))],
+        None => vec![Line::from("Not downloaded yet.")],
+    };
+    let details = Paragraph::new(details_text)
         .block(Block::default()
         .borders(Borders::ALL)
-        .title(" Stats "))
+        .title(" Model Details "))
+        .wrap(Wrap { trim: false });
+
+    // Split the Stats Area (live numbers on top, command output below)
+    let stats_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(7), Constraint::Min(3)])
+        .split(info_area[2]);
+
+    // THE STATS [0][2][0]
+    let stats_text = match &client.stats {
+        Some(Ok(stats)) => stats.lines(),
+        Some(Err(e)) => vec![format!("No stats: {}", e)],
+        None => vec!["Waiting for a running server with a loaded model...".to_string()],
+    };
+    let stats = Paragraph::new(stats_text.join("\n"))
+        .block(Block::default()
+        .borders(Borders::ALL)
+        .title(" Stats ")
+        .title_bottom(server_title(client)))
+        .wrap(Wrap { trim: false });
+
+    // THE OUTPUT [0][2][1]
+    let output = Paragraph::new(client.ter_text.join("\n"))
+        .block(Block::default()
+        .borders(Borders::ALL)
+        .title(" Output "))
         .wrap(Wrap { trim: false });
 
     // Split the Chat Area
@@ -168,10 +288,101 @@ pub fn show_chat(f: &mut Frame, app: &App, client: &LlamaClient) {
 
     // For the List
     f.render_stateful_widget(list, info_area[0], &mut state);
+    // For the Model Details
+    f.render_widget(details, info_area[1]);
     // For the Stats Window
-    f.render_widget(stats, info_area[1]);
+    f.render_widget(stats, stats_area[0]);
+    f.render_widget(output, stats_area[1]);
     // For the Chat Itself
     f.render_widget(chat, chat_area[0]);
     // For the Input
     f.render_widget(input_box, chat_area[1]);
-}
\ No newline at end of file
+}
+
+// LOG VIEWER SCREEN
+pub fn show_logs(f: &mut Frame, app: &mut App, client: &LlamaClient) {
+    let screen = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(3)])
+        .split(f.area());
+
+    // THE LOGS [0]
+    let lines = app.log_view.filter(&client.server_log);
+    let height = screen[0].height.saturating_sub(2) as usize;
+    // New filters or a resize can leave the scroll too far up
+    app.log_view.max_scroll = lines.len().saturating_sub(height);
+    app.log_view.scroll = app.log_view.scroll.min(app.log_view.max_scroll);
+    let scroll = app.log_view.scroll;
+    let end = lines.len() - scroll;
+    let start = end.saturating_sub(height);
+
+    let visible: Vec<Line> = lines[start..end]
+        .iter()
+        .map(|line| {
+            let color = match line.level {
+                LogLevel::Error => Color::Red,
+                LogLevel::Warn => Color::Yellow,
+                LogLevel::Info => Color::White,
+                LogLevel::Debug => Color::DarkGray,
+            };
+            Line::from(Span::styled(line.text.as_str(), Style::default().fg(color)))
+        })
+        .collect();
+
+    let mut title = format!(" Server Logs [{}] ", app.log_view.level.label());
+    if !app.log_view.search.is_empty() {
+        title.push_str(&format!("[search: {}] ", app.log_view.search));
+    }
+    let logs = Paragraph::new(visible)
+        .block(Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom(format!(" {} of {} lines  [UP/DOWN/PGUP/PGDN] scroll  [END] follow  [TAB] level ", lines.len(), client.server_log.lines.len())));
+
+    // THE INPUT [1]
+    let input_box = Paragraph::new(client.user_text.as_str())
+        .block(Block::default()
+        .borders(Borders::ALL)
+        .title(" Search ([ENTER] to apply, \"go config\" / \"go chat\" to leave) "));
+
+    f.render_widget(logs, screen[0]);
+    f.render_widget(input_box, screen[1]);
+}
+
+// " server: ready (pid 1234) [812 MB] ", colored by state
+fn server_title(client: &LlamaClient) -> Line<'static> {
+    let color = match client.server_state {
+        ServerState::Ready { .. } | ServerState::Connected { .. } => Color::Green,
+        ServerState::Loading { .. } | ServerState::Stopping | ServerState::Connecting => Color::Yellow,
+        ServerState::Failed(_) => Color::Red,
+        ServerState::Stopped => Color::DarkGray,
+    };
+    let mut title = format!(" server: {} ", client.server_state.label());
+    if let Some(total) = client.memory.total() {
+        title.push_str(&format!("[{}] ", format_bytes(total)));
+    }
+    Line::from(Span::styled(title, Style::default().fg(color)))
+}
+
+// One "Key: value" line per GGUF field
+fn model_details(info: &GgufInfo) -> Vec<Line<'static>> {
+    let field = |key: &str, value: String| Line::from(vec![
+        Span::styled(format!("{}: ", key), Style::default().fg(Color::Magenta)),
+        Span::raw(value),
+    ]);
+    let unknown = || "unknown".to_string();
+
+    let template = match &info.chat_template {
+        Some(template) => format!("embedded ({} chars)", template.len()),
+        None => "none".to_string(),
+    };
+    vec![
+        field("Name", info.name.clone().unwrap_or_else(unknown)),
+        field("Architecture", info.architecture.clone().unwrap_or_else(unknown)),
+        field("Parameters", format_parameters(info.parameters)),
+        field("Quantization", info.quantization.clone().unwrap_or_else(unknown)),
+        field("Context", info.context_length.map(|c| c.to_string()).unwrap_or_else(unknown)),
+        field("Chat template", template),
+        field("GGUF", format!("v{}", info.version)),
+    ]
+}